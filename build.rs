@@ -0,0 +1,155 @@
+//! Translates the bundled wat workloads with wasm2c and links them into the benchmark statically,
+//! so that they can be run without a toolchain and called directly rather than through
+//! `dlopen`ed symbols. If wasm2c isn't available, this is skipped and only the runtime path is
+//! available.
+
+use std::{fmt::Write, path::PathBuf, process::Command};
+
+use anyhow::{Context, Result};
+
+#[allow(dead_code)]
+#[path = "src/matmul.rs"]
+mod matmul;
+
+/// The workloads to build, by the module name wasm2c gives them (which must be a plain C
+/// identifier) and their source. The SIMD workloads aren't built, since wasm2c's output for them
+/// needs the SIMDe headers.
+const MODULES: &[(&str, &str)] = &[
+    ("add", "src/wat/add.wat"),
+    ("addmem", "src/wat/add-mem.wat"),
+    ("addvec", "src/wat/add-vec.wat"),
+    ("trapoob", "src/wat/trap-oob.wat"),
+    ("trapunreachable", "src/wat/trap-unreachable.wat"),
+    ("trapdivzero", "src/wat/trap-div-zero.wat"),
+    ("trapstackoverflow", "src/wat/trap-stack-overflow.wat"),
+    ("grow", "src/wat/grow.wat"),
+    ("fib", "src/wat/fib.wat"),
+    ("ackermann", "src/wat/ackermann.wat"),
+    ("interpreter", "src/wat/interpreter.wat"),
+    ("fmatmul", "src/wat/fmatmul.wat"),
+    ("nbody", "src/wat/nbody.wat"),
+];
+
+/// The dimensions to build matrix multiplication workloads for, as `matmul<dim>`: those
+/// `matmul-sweep` runs by default.
+const MATMUL_DIMS: &[u32] = &[8, 16, 32, 64, 128, 256];
+
+/// The version of wasm2c the runtime files in `wasm2c/` come from, which the crate reads as
+/// `env!("WASM2C_VERSION")`.
+const WASM2C_VERSION: &str = "1.0.34";
+
+fn main() -> Result<()> {
+    println!("cargo::rustc-check-cfg=cfg(wasm2c_aot)");
+    println!("cargo::rustc-env=WASM2C_VERSION={WASM2C_VERSION}");
+    println!("cargo::rerun-if-changed=wasm2c");
+    println!("cargo::rerun-if-changed=src/matmul.rs");
+    println!("cargo::rerun-if-changed=src/wat/matmul.wat");
+    for (_, path) in MODULES {
+        println!("cargo::rerun-if-changed={path}");
+    }
+    println!("cargo::rerun-if-env-changed=PATH");
+
+    let version = Command::new("wasm2c").arg("--version").output();
+    let version = match &version {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout),
+        _ => {
+            println!(
+                "cargo::warning=wasm2c not found; ahead-of-time wasm2c modules won't be built"
+            );
+            return Ok(());
+        }
+    };
+    if version.trim() != WASM2C_VERSION {
+        println!(
+            "cargo::warning=found wasm2c {}, but the bundled runtime is from wasm2c {WASM2C_VERSION}; ahead-of-time wasm2c modules won't be built",
+            version.trim()
+        );
+        return Ok(());
+    }
+
+    let mut modules = vec![];
+    for (name, path) in MODULES {
+        let wat = std::fs::read(path)?;
+        modules.push((name.to_string(), path.to_string(), wat));
+    }
+    for dim in MATMUL_DIMS {
+        let wat = matmul::wat(*dim).into_bytes();
+        modules.push((format!("matmul{dim}"), "src/wat/matmul.wat".into(), wat));
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    let mut build = cc::Build::new();
+    let mut table = String::new();
+    writeln!(
+        table,
+        "#include <stddef.h>\n#include \"wasm-rt.h\"\n#include \"wasm-rt-impl.h\"\n"
+    )?;
+    for (name, path, wat) in &modules {
+        let wasm = wabt::wat2wasm(wat)
+            .with_context(|| format!("could not assemble {name} from {path}"))?;
+        let wasm_file = out_dir.join(format!("{name}.wasm"));
+        let c_file = out_dir.join(format!("{name}.c"));
+        std::fs::write(&wasm_file, wasm)?;
+        let status = Command::new("wasm2c")
+            .arg("-o")
+            .arg(&c_file)
+            .args(["-n", name.as_str()])
+            .arg(&wasm_file)
+            .status()?;
+        anyhow::ensure!(status.success(), "wasm2c failed on {path}");
+        build.file(&c_file);
+        writeln!(table, "#include \"{name}.h\"")?;
+    }
+
+    // A wrapper catching traps from each module's `add`, as `module_try_add` in `wasm2c/lib.c`.
+    for (name, _, _) in &modules {
+        writeln!(
+            table,
+            "\nstatic wasm_rt_trap_t {name}_try_add(void* module, u32 lhs, u32 rhs, u32* result) {{\n  WASM_RT_SAVE_STACK_DEPTH();\n  wasm_rt_trap_t trap = WASM_RT_SETJMP(g_wasm_rt_jmp_buf);\n  if (trap == WASM_RT_TRAP_NONE) {{\n    *result = w2c_{name}_add(module, lhs, rhs);\n  }}\n  return trap;\n}}"
+        )?;
+    }
+
+    // A table of every module, matching `AotModule` in `src/wasm2c/aot.rs`.
+    writeln!(
+        table,
+        "\ntypedef struct {{\n  const char* name;\n  size_t size;\n  void (*instantiate)(void*);\n  u32 (*add)(void*, u32, u32);\n  wasm_rt_trap_t (*try_add)(void*, u32, u32, u32*);\n  void (*free)(void*);\n}} aot_module_t;\n"
+    )?;
+    writeln!(table, "const aot_module_t aot_modules[] = {{")?;
+    for (name, _, _) in &modules {
+        writeln!(
+            table,
+            "  {{\"{name}\", sizeof(w2c_{name}), (void (*)(void*))wasm2c_{name}_instantiate,\n   (u32 (*)(void*, u32, u32))w2c_{name}_add, {name}_try_add,\n   (void (*)(void*))wasm2c_{name}_free}},"
+        )?;
+    }
+    writeln!(table, "}};\n")?;
+    writeln!(
+        table,
+        "const size_t aot_modules_len = sizeof(aot_modules) / sizeof(aot_modules[0]);"
+    )?;
+    let table_file = out_dir.join("aot.c");
+    std::fs::write(&table_file, table)?;
+
+    // The runtime is linked in once, so every module shares its configuration: guard pages,
+    // with the signal handler catching out-of-bounds accesses, as in `RuntimeConfig::MMAP`.
+    build
+        .file(&table_file)
+        .file("wasm2c/wasm-rt-impl.c")
+        .include("wasm2c")
+        .include(&out_dir)
+        .define("WASM_RT_USE_MMAP", "1")
+        .define("WASM_RT_MEMCHECK_GUARD_PAGES", "1")
+        .define("WASM_RT_MEMCHECK_BOUNDS_CHECK", "0")
+        .define("WASM_RT_USE_STACK_DEPTH_COUNT", "0")
+        .define("WASM_RT_SKIP_SIGNAL_RECOVERY", "0")
+        .define("WASM_RT_INSTALL_SIGNAL_HANDLER", "1")
+        .opt_level(2)
+        .flag("-fno-optimize-sibling-calls")
+        .flag("-ffp-contract=off")
+        .flag("-frounding-math")
+        .flag("-fsignaling-nans")
+        .warnings(false)
+        .compile("wasm2c_aot");
+    println!("cargo::rustc-link-lib=m");
+    println!("cargo::rustc-cfg=wasm2c_aot");
+    Ok(())
+}
@@ -7,6 +7,9 @@ def plot_benchmark(benchmark):
     ax = fig.subplots()
     print(benchmark)
     for approach in sorted(os.listdir(benchmark)):
+        # skip sidecar files such as V8 heap statistics (*.heap.csv)
+        if approach.count('.') != 1:
+            continue
         approach = approach[:-4]
         print(f"{benchmark}/{approach}")
         with open(f"{benchmark}/{approach}.csv", 'r') as f:
@@ -37,9 +40,51 @@ def plot_benchmark(benchmark):
     ax.legend()
     plt.savefig(f"{benchmark}.png")
 
+def single_threaded_us(path):
+    with open(path, 'r') as f:
+        for row in csv.DictReader(f):
+            if int(row['parallel']) == 1:
+                iterations = float(row['iterations'])
+                duration_s = float(row['duration_ns'])/1e9
+                return 1e6/(iterations / duration_s)
+    return None
+
+# plot a `matmul-sweep` run: single-threaded time per call against the matrix dimension
+def plot_matmul_sweep(benchmarks):
+    dims = sorted(int(benchmark[len("matmul"):]) for benchmark in benchmarks)
+    approaches = set()
+    for dim in dims:
+        approaches.update(approach[:-4] for approach in os.listdir(f"matmul{dim}") if approach.count('.') == 1)
+    fig = plt.figure()
+    ax = fig.subplots()
+    for approach in sorted(approaches):
+        X = []
+        Y = []
+        for dim in dims:
+            path = f"matmul{dim}/{approach}.csv"
+            if not os.path.exists(path):
+                continue
+            y = single_threaded_us(path)
+            if y is not None:
+                X += [dim]
+                Y += [y]
+        ax.plot(X, Y, marker='o', label=approach)
+    ax.set_xscale('log', base=2)
+    ax.set_yscale('log')
+    ax.set_ylabel('µS per iteration (1 thread)')
+    ax.set_xlabel('matrix dimension')
+    ax.set_title('matmul')
+    ax.legend()
+    plt.savefig("matmul-sweep.png")
+
 for benchmark in sorted(os.listdir(".")):
     if not os.path.isdir(benchmark):
         continue
     plot_benchmark(benchmark)
 
+matmuls = [benchmark for benchmark in os.listdir(".")
+           if os.path.isdir(benchmark) and benchmark.startswith("matmul") and benchmark[len("matmul"):].isdigit()]
+if len(matmuls) > 1:
+    plot_matmul_sweep(matmuls)
+
 
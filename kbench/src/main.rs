@@ -15,7 +15,20 @@ use core::time::Duration;
 
 #[kmain]
 async fn kmain(argv: &[usize]) {
-    let &[offset, len, warmup, duration, output_offset, output_length] = argv else {
+    let &[
+        offset,
+        len,
+        warmup,
+        duration,
+        output_offset,
+        output_length,
+        faults,
+        lhs,
+        rhs,
+        expected,
+        payload,
+    ] = argv
+    else {
         todo!();
     };
     let parallel = output_length;
@@ -37,6 +50,7 @@ async fn kmain(argv: &[usize]) {
     let Value::Lambda(lambda) = result else {
         panic!("expected lambda, got {result:x?}");
     };
+    let faults = faults != 0;
     let warmup = Duration::from_nanos(warmup as u64);
     let duration = Duration::from_nanos(duration as u64);
 
@@ -50,6 +64,10 @@ async fn kmain(argv: &[usize]) {
             notready.clone(),
             notdone.clone(),
             lambda.clone(),
+            faults,
+            (lhs, rhs),
+            expected,
+            payload,
         )));
     }
     for (x, y) in set.into_iter().zip(output.iter()) {
@@ -78,12 +96,60 @@ async fn run(
     notready: Arc<AtomicUsize>,
     notdone: Arc<AtomicUsize>,
     lambda: Lambda,
+    faults: bool,
+    (lhs, rhs): (usize, usize),
+    expected: usize,
+    payload: usize,
 ) -> usize {
-    let once = || {
+    let apply = |x, y| {
         let lambda = core::hint::black_box(lambda.clone());
-        let thunk = lambda.apply(Value::Tree(vec![Value::Word(1), Value::Word(2)].into()));
+        let thunk = lambda.apply(Value::Tree(vec![Value::Word(x), Value::Word(y)].into()));
         core::hint::black_box(thunk.run())
     };
+    // A payload workload is applied to the payload's length and the payload itself, and must
+    // return its output.
+    let payload = (payload != 0).then(|| {
+        let mut input = vec![0; payload];
+        kernels::payload::fill(payload as u32, &mut input);
+        let mut output = vec![0; payload];
+        kernels::payload::transform(&input, &mut output);
+        (input, output)
+    });
+    let transfer = |(input, output): &(Vec<u8>, Vec<u8>)| {
+        let lambda = core::hint::black_box(lambda.clone());
+        let argument = Value::Tree(
+            vec![
+                Value::Word(input.len() as _),
+                Value::Blob(input.as_slice().into()),
+            ]
+            .into(),
+        );
+        let result = core::hint::black_box(lambda.apply(argument).run());
+        assert!(
+            matches!(&result, Value::Blob(blob) if blob[..] == output[..]),
+            "workload returned the wrong output"
+        );
+    };
+    // A workload which faults never gets to return a word. `usize::MAX` means the result of
+    // one which returns isn't checked.
+    let once = || {
+        if let Some(payload) = &payload {
+            transfer(payload);
+            return;
+        }
+        let result = apply(lhs as _, rhs as _);
+        if faults {
+            assert!(
+                !matches!(result, Value::Word(_)),
+                "expected a fault, got {result:x?}"
+            );
+        } else if expected != usize::MAX {
+            assert!(
+                matches!(result, Value::Word(word) if word as u64 == expected as u64),
+                "expected {expected}, got {result:x?}"
+            );
+        }
+    };
 
     let warmup_start = kvmclock::time_since_boot();
     while kvmclock::time_since_boot() - warmup_start < warmup {
@@ -108,5 +174,13 @@ async fn run(
     while notready.load(Ordering::Acquire) != 0 {
         once();
     }
+    if faults {
+        // Check that a call which doesn't fault still succeeds afterwards.
+        let result = apply(0, 2);
+        assert!(
+            matches!(result, Value::Word(2)),
+            "didn't recover from faults: got {result:x?}"
+        );
+    }
     iters
 }
@@ -0,0 +1,41 @@
+//! Call-intensive workloads: naive recursive Fibonacci, Ackermann's function, and an
+//! interpreter dispatching each step through a table of functions. The wasm and JavaScript
+//! builds are written by hand to match these.
+
+/// The `n`th Fibonacci number (wrapping), computed with exponentially many calls.
+pub fn fib(n: u32) -> u32 {
+    if n < 2 {
+        n
+    } else {
+        fib(n - 1).wrapping_add(fib(n - 2))
+    }
+}
+
+/// Ackermann's function. With `m = 2`, its calls nest about `2 * n` deep.
+pub fn ackermann(m: u32, n: u32) -> u32 {
+    match (m, n) {
+        (0, n) => n.wrapping_add(1),
+        (m, 0) => ackermann(m - 1, 1),
+        (m, n) => ackermann(m - 1, ackermann(m, n - 1)),
+    }
+}
+
+/// The interpreter's instructions, which each take the accumulator and the step number and
+/// return the new accumulator.
+pub const INSTRUCTIONS: [fn(u32, u32) -> u32; 4] = [
+    |acc, i| acc.wrapping_add(i),
+    |acc, _| acc.wrapping_mul(3),
+    |acc, i| acc ^ i,
+    |acc, _| acc >> 1,
+];
+
+/// Run the interpreter for `steps` steps, each calling the instruction picked by the
+/// accumulator and step number.
+pub fn interpret(steps: u32) -> u32 {
+    let mut acc = 0;
+    for i in 0..steps {
+        let instruction = INSTRUCTIONS[((acc ^ i) & 3) as usize];
+        acc = core::hint::black_box(instruction)(acc, i);
+    }
+    acc
+}
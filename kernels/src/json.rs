@@ -0,0 +1,224 @@
+//! Parse a 16 KiB JSON document of records and sum their `value` fields.
+
+use crate::Rng;
+
+const INPUT_SIZE: usize = 16 << 10;
+
+/// Writes bytes into a fixed buffer, stopping silently when it's full.
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl Writer<'_> {
+    fn push(&mut self, bytes: &[u8]) -> bool {
+        let Some(space) = self.buffer.get_mut(self.len..self.len + bytes.len()) else {
+            return false;
+        };
+        space.copy_from_slice(bytes);
+        self.len += bytes.len();
+        true
+    }
+
+    fn number(&mut self, n: i64) -> bool {
+        let mut digits = [0u8; 20];
+        let mut i = digits.len();
+        let mut m = n.unsigned_abs();
+        loop {
+            i -= 1;
+            digits[i] = b'0' + (m % 10) as u8;
+            m /= 10;
+            if m == 0 {
+                break;
+            }
+        }
+        (n >= 0 || self.push(b"-")) && self.push(&digits[i..])
+    }
+}
+
+/// Fill `buffer` with a JSON array of records, returning its length.
+fn generate(seed: u32, buffer: &mut [u8]) -> usize {
+    const NAMES: &[&[u8]] = &[
+        b"alpha",
+        b"beta",
+        b"gamma",
+        b"delta \\\"quoted\\\"",
+        b"epsilon",
+    ];
+    let mut rng = Rng::new(seed);
+    let mut out = Writer { buffer, len: 0 };
+    // Leave room for the closing bracket.
+    let limit = out.buffer.len() - 1;
+    out.push(b"[");
+    for id in 0.. {
+        let start = out.len;
+        let value = rng.below(20_000) as i64 - 10_000;
+        let complete = (id == 0 || out.push(b",\n  "))
+            && out.push(b"{\"id\": ")
+            && out.number(id)
+            && out.push(b", \"name\": \"")
+            && out.push(NAMES[rng.below(NAMES.len() as u32) as usize])
+            && out.push(b"\", \"value\": ")
+            && out.number(value)
+            && out.push(b", \"tags\": [true, false, null], \"ratio\": 0.5e1}")
+            && out.len <= limit;
+        if !complete {
+            out.len = start;
+            break;
+        }
+    }
+    out.push(b"]");
+    out.len
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    sum: i64,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r' | b'\t')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        (self.peek()? == byte).then(|| self.position += 1)
+    }
+
+    fn literal(&mut self, literal: &[u8]) -> Option<()> {
+        let end = self.position + literal.len();
+        (self.input.get(self.position..end)? == literal).then(|| self.position = end)
+    }
+
+    /// Parse a string, returning its raw (still escaped) contents.
+    fn string(&mut self) -> Option<&'a [u8]> {
+        self.expect(b'"')?;
+        let start = self.position;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => self.position += 2,
+                _ => self.position += 1,
+            }
+        }
+        self.position += 1;
+        let input = self.input;
+        input.get(start..self.position - 1)
+    }
+
+    /// Parse a number, returning its integer part.
+    fn number(&mut self) -> Option<i64> {
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.position += 1;
+        }
+        let mut n: i64 = 0;
+        let start = self.position;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            n = n.checked_mul(10)?.checked_add((digit - b'0') as i64)?;
+            self.position += 1;
+        }
+        if self.position == start {
+            return None;
+        }
+        if self.peek() == Some(b'.') {
+            self.position += 1;
+            while matches!(self.peek(), Some(b'0'..=b'9')) {
+                self.position += 1;
+            }
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.position += 1;
+            }
+            while matches!(self.peek(), Some(b'0'..=b'9')) {
+                self.position += 1;
+            }
+        }
+        Some(if negative { -n } else { n })
+    }
+
+    /// Parse any value, adding it to the sum if it's a number under the key `value`.
+    fn value(&mut self, key: Option<&[u8]>) -> Option<()> {
+        self.skip_whitespace();
+        match self.peek()? {
+            b'{' => {
+                self.position += 1;
+                self.skip_whitespace();
+                if self.peek()? == b'}' {
+                    self.position += 1;
+                    return Some(());
+                }
+                loop {
+                    let key = self.string()?;
+                    self.expect(b':')?;
+                    self.value(Some(key))?;
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b',' => self.position += 1,
+                        b'}' => break,
+                        _ => return None,
+                    }
+                }
+                self.position += 1;
+            }
+            b'[' => {
+                self.position += 1;
+                self.skip_whitespace();
+                if self.peek()? == b']' {
+                    self.position += 1;
+                    return Some(());
+                }
+                loop {
+                    self.value(None)?;
+                    self.skip_whitespace();
+                    match self.peek()? {
+                        b',' => self.position += 1,
+                        b']' => break,
+                        _ => return None,
+                    }
+                }
+                self.position += 1;
+            }
+            b'"' => {
+                self.string()?;
+            }
+            b't' => self.literal(b"true")?,
+            b'f' => self.literal(b"false")?,
+            b'n' => self.literal(b"null")?,
+            _ => {
+                let n = self.number()?;
+                if key == Some(b"value") {
+                    self.sum += n;
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+/// Sum every number under the key `value` in a JSON document, or `None` if it's malformed.
+pub fn sum(input: &[u8]) -> Option<i64> {
+    let mut parser = Parser {
+        input,
+        position: 0,
+        sum: 0,
+    };
+    parser.value(None)?;
+    parser.skip_whitespace();
+    (parser.position == input.len()).then_some(parser.sum)
+}
+
+pub fn run(seed: u32, scratch: &mut [u8]) -> u32 {
+    let len = generate(seed, &mut scratch[..INPUT_SIZE]);
+    sum(&scratch[..len]).expect("generated JSON is well-formed") as u32
+}
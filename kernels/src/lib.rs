@@ -0,0 +1,50 @@
+//! Realistic compute kernels, shared by the wasm workloads in `wbench`, the Arca workloads in
+//! `ubench`, and the benchmark itself, which runs them natively to check the guests' results.
+//!
+//! Each workload generates its input from a seed into a caller-provided scratch buffer (the
+//! guests have no allocator), processes it, and returns a 32-bit digest of the output.
+
+#![no_std]
+
+pub mod calls;
+pub mod json;
+pub mod lz;
+pub mod nbody;
+pub mod payload;
+pub mod regex;
+pub mod sha256;
+pub mod thumbnail;
+
+/// How much scratch memory every workload needs.
+pub const SCRATCH_SIZE: usize = 1 << 18;
+
+/// A small, fast pseudo-random generator for building inputs (xorshift32).
+pub struct Rng(u32);
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        // xorshift gets stuck at zero.
+        Rng(seed.wrapping_mul(0x9e37_79b9) | 1)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    /// A number in `0..n`.
+    pub fn below(&mut self, n: u32) -> u32 {
+        self.next_u32() % n
+    }
+}
+
+/// FNV-1a, for digesting outputs.
+pub fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
@@ -0,0 +1,146 @@
+//! LZ77-style compression and decompression of 16 KiB of generated text.
+//!
+//! The format is a sequence of tokens: a byte `n < 0x80` followed by `n + 1` literal bytes, or a
+//! byte `0x80 | (length - MIN_MATCH)` followed by a little-endian 16-bit offset back into the
+//! output.
+
+use crate::{Rng, fnv1a};
+
+const INPUT_SIZE: usize = 16 << 10;
+const MIN_MATCH: usize = 4;
+const MAX_MATCH: usize = MIN_MATCH + 0x7f;
+const MAX_LITERALS: usize = 0x80;
+const HASH_BITS: u32 = 12;
+
+/// The most `compress` can write for an input of `len` bytes.
+pub const fn max_compressed_len(len: usize) -> usize {
+    len + len.div_ceil(MAX_LITERALS)
+}
+
+fn hash(bytes: &[u8]) -> usize {
+    let word = u32::from_le_bytes(bytes[..4].try_into().unwrap());
+    (word.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+/// Compress `input` into `output`, using `table` (at least `2 << HASH_BITS` bytes) to remember
+/// where each hash was last seen. Returns the compressed length.
+pub fn compress(input: &[u8], output: &mut [u8], table: &mut [u8]) -> usize {
+    assert!(input.len() <= u16::MAX as usize);
+    let table = &mut table[..2 << HASH_BITS];
+    table.fill(0);
+    let mut out = 0;
+    let mut literals = 0;
+    let mut i = 0;
+    let flush = |output: &mut [u8], out: &mut usize, start: usize, end: usize| {
+        for chunk in input[start..end].chunks(MAX_LITERALS) {
+            output[*out] = (chunk.len() - 1) as u8;
+            output[*out + 1..*out + 1 + chunk.len()].copy_from_slice(chunk);
+            *out += 1 + chunk.len();
+        }
+    };
+    while i + MIN_MATCH <= input.len() {
+        let h = hash(&input[i..]) * 2;
+        // Positions are stored plus one, so that zero means empty.
+        let candidate = u16::from_le_bytes([table[h], table[h + 1]]) as usize;
+        table[h..h + 2].copy_from_slice(&(i as u16 + 1).to_le_bytes());
+        if candidate != 0 {
+            let candidate = candidate - 1;
+            let length = input[candidate..]
+                .iter()
+                .zip(&input[i..])
+                .take(MAX_MATCH)
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length >= MIN_MATCH {
+                flush(output, &mut out, literals, i);
+                output[out] = 0x80 | (length - MIN_MATCH) as u8;
+                output[out + 1..out + 3].copy_from_slice(&((i - candidate) as u16).to_le_bytes());
+                out += 3;
+                i += length;
+                literals = i;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    flush(output, &mut out, literals, input.len());
+    out
+}
+
+/// Decompress `input` into `output`, returning the decompressed length, or `None` if it's
+/// malformed.
+pub fn decompress(input: &[u8], output: &mut [u8]) -> Option<usize> {
+    let mut i = 0;
+    let mut out = 0;
+    while i < input.len() {
+        let token = input[i] as usize;
+        i += 1;
+        if token < 0x80 {
+            let len = token + 1;
+            output
+                .get_mut(out..out + len)?
+                .copy_from_slice(input.get(i..i + len)?);
+            i += len;
+            out += len;
+        } else {
+            let len = (token & 0x7f) + MIN_MATCH;
+            let offset = u16::from_le_bytes(input.get(i..i + 2)?.try_into().unwrap()) as usize;
+            i += 2;
+            if offset == 0 || offset > out || out + len > output.len() {
+                return None;
+            }
+            // Matches may overlap their own output, so copy byte by byte.
+            for j in out..out + len {
+                output[j] = output[j - offset];
+            }
+            out += len;
+        }
+    }
+    Some(out)
+}
+
+/// Fill `buffer` with text made of a small vocabulary, which compresses well but not trivially.
+fn generate(seed: u32, buffer: &mut [u8]) {
+    const WORDS: &[&[u8]] = &[
+        b"the",
+        b"isolation",
+        b"of",
+        b"functions",
+        b"sandbox",
+        b"memory",
+        b"kernel",
+        b"and",
+        b"a",
+        b"benchmark",
+        b"call",
+        b"request",
+        b"with",
+        b"page",
+        b"table",
+        b"runtime",
+    ];
+    let mut rng = Rng::new(seed);
+    let mut i = 0;
+    while i < buffer.len() {
+        let word = WORDS[rng.below(WORDS.len() as u32) as usize];
+        let separator: &[u8] = if rng.below(12) == 0 { b".\n" } else { b" " };
+        for &byte in word.iter().chain(separator) {
+            if i == buffer.len() {
+                break;
+            }
+            buffer[i] = byte;
+            i += 1;
+        }
+    }
+}
+
+pub fn run(seed: u32, scratch: &mut [u8]) -> u32 {
+    let (input, rest) = scratch.split_at_mut(INPUT_SIZE);
+    let (compressed, rest) = rest.split_at_mut(max_compressed_len(INPUT_SIZE));
+    let (decompressed, table) = rest.split_at_mut(INPUT_SIZE);
+    generate(seed, input);
+    let len = compress(input, compressed, table);
+    let decompressed_len = decompress(&compressed[..len], decompressed);
+    assert!(decompressed_len == Some(INPUT_SIZE) && *input == *decompressed);
+    fnv1a(&compressed[..len])
+}
@@ -0,0 +1,66 @@
+//! The n-body workloads' initial state, shared by their native reference implementation
+//! (`benchmark::nbody`) and their Arca builds. The wasm and JavaScript builds spell it out in their
+//! own sources.
+
+/// The five bodies, as in the workloads' memory: the position, a pad, the velocity and the mass.
+/// The sun's velocity offsets the planets' momentum.
+pub const BODIES: [[f64; 8]; 5] = [
+    // Sun
+    [
+        0.0,
+        0.0,
+        0.0,
+        0.0,
+        -0.00038766340719874267,
+        -0.0032753590371765707,
+        2.3935734080003e-05,
+        39.47841760435743,
+    ],
+    // Jupiter
+    [
+        4.841431442464721,
+        -1.1603200440274284,
+        -0.10362204447112311,
+        0.0,
+        0.606326392995832,
+        2.81198684491626,
+        -0.02521836165988763,
+        0.03769367487038949,
+    ],
+    // Saturn
+    [
+        8.34336671824458,
+        4.124798564124305,
+        -0.4035234171143214,
+        0.0,
+        -1.0107743461787924,
+        1.8256623712304119,
+        0.008415761376584154,
+        0.011286326131968767,
+    ],
+    // Uranus
+    [
+        12.894369562139131,
+        -15.111151401698631,
+        -0.22330757889265573,
+        0.0,
+        1.0827910064415354,
+        0.8687130181696082,
+        -0.010832637401363636,
+        0.0017237240570597112,
+    ],
+    // Neptune
+    [
+        15.379697114850917,
+        -25.919314609987964,
+        0.17925877295037118,
+        0.0,
+        0.979090732243898,
+        0.5946989986476762,
+        -0.034755955504078104,
+        0.0020336868699246304,
+    ],
+];
+
+/// The time step.
+pub const DT: f64 = 0.01;
@@ -0,0 +1,20 @@
+//! The buffers the payload workloads pass into and out of the sandbox. The workload itself is
+//! deliberately trivial (it inverts every byte) so that moving the buffers dominates.
+
+use crate::Rng;
+
+/// Fill `input` with the payload for `seed`.
+pub fn fill(seed: u32, input: &mut [u8]) {
+    let mut rng = Rng::new(seed);
+    for chunk in input.chunks_mut(4) {
+        chunk.copy_from_slice(&rng.next_u32().to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// Write the output for `input` into `output`, which must be the same length.
+pub fn transform(input: &[u8], output: &mut [u8]) {
+    assert_eq!(input.len(), output.len());
+    for (output, input) in output.iter_mut().zip(input) {
+        *output = !input;
+    }
+}
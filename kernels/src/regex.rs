@@ -0,0 +1,193 @@
+//! Count the lines of 16 KiB of generated text which match an e-mail-like regular expression,
+//! with a small backtracking matcher.
+//!
+//! The matcher supports literals, `.`, classes like `[a-z0-9_]`, `\` escapes, the quantifiers
+//! `*`, `+` and `?`, and the anchors `^` and `$`.
+
+use crate::Rng;
+
+const INPUT_SIZE: usize = 16 << 10;
+const PATTERN: &[u8] = br"[a-z_]+[0-9]*@[a-z]+\.[a-z]+$";
+const MAX_TOKENS: usize = 32;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Repeat {
+    Once,
+    /// `?`
+    Optional,
+    /// `*`
+    Any,
+    /// `+`
+    Some,
+}
+
+/// A set of bytes.
+#[derive(Copy, Clone)]
+struct Class([u32; 8]);
+
+impl Class {
+    const EMPTY: Class = Class([0; 8]);
+
+    fn insert(&mut self, byte: u8) {
+        self.0[byte as usize / 32] |= 1 << (byte % 32);
+    }
+
+    fn contains(&self, byte: u8) -> bool {
+        self.0[byte as usize / 32] & (1 << (byte % 32)) != 0
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Token {
+    class: Class,
+    repeat: Repeat,
+}
+
+/// A compiled pattern.
+pub struct Regex {
+    tokens: [Token; MAX_TOKENS],
+    len: usize,
+    start: bool,
+    end: bool,
+}
+
+impl Regex {
+    /// Compile `pattern`, returning `None` if it's malformed or too long.
+    pub fn new(mut pattern: &[u8]) -> Option<Regex> {
+        let mut regex = Regex {
+            tokens: [Token {
+                class: Class::EMPTY,
+                repeat: Repeat::Once,
+            }; MAX_TOKENS],
+            len: 0,
+            start: false,
+            end: false,
+        };
+        if let [b'^', rest @ ..] = pattern {
+            regex.start = true;
+            pattern = rest;
+        }
+        if let [rest @ .., b'$'] = pattern {
+            regex.end = true;
+            pattern = rest;
+        }
+        let mut i = 0;
+        while i < pattern.len() {
+            let mut class = Class::EMPTY;
+            match pattern[i] {
+                b'.' => class.0 = [u32::MAX; 8],
+                b'\\' => {
+                    i += 1;
+                    class.insert(*pattern.get(i)?);
+                }
+                b'[' => {
+                    i += 1;
+                    while *pattern.get(i)? != b']' {
+                        let low = pattern[i];
+                        if pattern.get(i + 1) == Some(&b'-') && pattern.get(i + 2) != Some(&b']') {
+                            let high = *pattern.get(i + 2)?;
+                            for byte in low..=high {
+                                class.insert(byte);
+                            }
+                            i += 3;
+                        } else {
+                            class.insert(low);
+                            i += 1;
+                        }
+                    }
+                }
+                b'*' | b'+' | b'?' | b']' => return None,
+                byte => class.insert(byte),
+            }
+            i += 1;
+            let repeat = match pattern.get(i) {
+                Some(b'?') => Repeat::Optional,
+                Some(b'*') => Repeat::Any,
+                Some(b'+') => Repeat::Some,
+                _ => Repeat::Once,
+            };
+            if repeat != Repeat::Once {
+                i += 1;
+            }
+            regex.push(class, repeat)?;
+        }
+        Some(regex)
+    }
+
+    fn push(&mut self, class: Class, repeat: Repeat) -> Option<()> {
+        *self.tokens.get_mut(self.len)? = Token { class, repeat };
+        self.len += 1;
+        Some(())
+    }
+
+    /// Whether the tokens from `token` on match a prefix of `text` (all of it, if anchored at the
+    /// end).
+    fn match_here(&self, token: usize, text: &[u8]) -> bool {
+        let Some(&Token { class, repeat }) = self.tokens[..self.len].get(token) else {
+            return !self.end || text.is_empty();
+        };
+        let (min, max) = match repeat {
+            Repeat::Once => (1, 1),
+            Repeat::Optional => (0, 1),
+            Repeat::Any => (0, usize::MAX),
+            Repeat::Some => (1, usize::MAX),
+        };
+        let available = text
+            .iter()
+            .take(max)
+            .take_while(|&&byte| class.contains(byte))
+            .count();
+        // Greedy, backing off one byte at a time.
+        (min..=available)
+            .rev()
+            .any(|n| self.match_here(token + 1, &text[n..]))
+    }
+
+    /// Whether the pattern matches anywhere in `text`.
+    pub fn is_match(&self, text: &[u8]) -> bool {
+        if self.start {
+            return self.match_here(0, text);
+        }
+        (0..=text.len()).any(|i| self.match_here(0, &text[i..]))
+    }
+}
+
+/// Fill `buffer` with lines, about half of which are e-mail addresses.
+fn generate(seed: u32, buffer: &mut [u8]) {
+    const NAMES: &[&[u8]] = &[b"alice", b"bob", b"carol_", b"x"];
+    const DIGITS: &[&[u8]] = &[b"", b"42", b"7"];
+    const HOSTS: &[&[u8]] = &[b"example", b"mail", b"a"];
+    const TLDS: &[&[u8]] = &[b".com", b".org", b".", b""];
+    const NOISE: &[&[u8]] = &[b" ", b"@", b"#", b"bob", b"."];
+    let mut rng = Rng::new(seed);
+    let mut pick = |parts: &[&'static [u8]]| parts[rng.below(parts.len() as u32) as usize];
+    let mut i = 0;
+    while i < buffer.len() {
+        let line = [
+            pick(NAMES),
+            pick(DIGITS),
+            b"@",
+            pick(HOSTS),
+            pick(TLDS),
+            pick(NOISE),
+            b"\n",
+        ];
+        for &byte in line.iter().copied().flatten() {
+            if i == buffer.len() {
+                return;
+            }
+            buffer[i] = byte;
+            i += 1;
+        }
+    }
+}
+
+pub fn run(seed: u32, scratch: &mut [u8]) -> u32 {
+    let input = &mut scratch[..INPUT_SIZE];
+    generate(seed, input);
+    let regex = Regex::new(PATTERN).expect("the pattern is valid");
+    input
+        .split(|&byte| byte == b'\n')
+        .filter(|line| regex.is_match(line))
+        .count() as u32
+}
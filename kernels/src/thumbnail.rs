@@ -0,0 +1,51 @@
+//! Downscale a generated 256x256 RGB image to a 32x32 thumbnail by averaging each 8x8 block.
+
+use crate::{Rng, fnv1a};
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 256;
+const SCALE: usize = 8;
+const CHANNELS: usize = 3;
+
+/// Downscale `image` (`width` by `height` pixels, row-major RGB) by `scale` in each dimension,
+/// writing the result to `thumbnail`.
+pub fn downscale(image: &[u8], width: usize, height: usize, scale: usize, thumbnail: &mut [u8]) {
+    let (thumb_width, thumb_height) = (width / scale, height / scale);
+    for ty in 0..thumb_height {
+        for tx in 0..thumb_width {
+            let mut sums = [0u32; CHANNELS];
+            for y in ty * scale..(ty + 1) * scale {
+                let row = &image[(y * width + tx * scale) * CHANNELS..][..scale * CHANNELS];
+                for pixel in row.chunks_exact(CHANNELS) {
+                    for (sum, &value) in sums.iter_mut().zip(pixel) {
+                        *sum += value as u32;
+                    }
+                }
+            }
+            let out = &mut thumbnail[(ty * thumb_width + tx) * CHANNELS..][..CHANNELS];
+            for (out, sum) in out.iter_mut().zip(sums) {
+                *out = (sum / (scale * scale) as u32) as u8;
+            }
+        }
+    }
+}
+
+/// Fill `image` with gradients and noise.
+fn generate(seed: u32, image: &mut [u8]) {
+    let mut rng = Rng::new(seed);
+    for (i, pixel) in image.chunks_exact_mut(CHANNELS).enumerate() {
+        let (x, y) = (i % WIDTH, i / WIDTH);
+        let noise = rng.next_u32();
+        pixel[0] = x as u8 ^ noise as u8;
+        pixel[1] = y as u8 ^ (noise >> 8) as u8;
+        pixel[2] = (x + y) as u8 ^ (noise >> 16) as u8;
+    }
+}
+
+pub fn run(seed: u32, scratch: &mut [u8]) -> u32 {
+    let (image, thumbnail) = scratch.split_at_mut(WIDTH * HEIGHT * CHANNELS);
+    let thumbnail = &mut thumbnail[..(WIDTH / SCALE) * (HEIGHT / SCALE) * CHANNELS];
+    generate(seed, image);
+    downscale(image, WIDTH, HEIGHT, SCALE, thumbnail);
+    fnv1a(thumbnail)
+}
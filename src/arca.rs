@@ -1,27 +1,178 @@
+use std::borrow::Cow;
 use std::sync::{
     Arc,
     atomic::{AtomicUsize, Ordering},
 };
 use std::time::Duration;
 
-use crate::Benchmark;
+use crate::{Arguments, Benchmark};
+
+use anyhow::{Context, Result};
 
 use vmm::runtime::{Mmap, Runtime};
 
 const KERNEL_ELF: &[u8] = include_bytes!(env!("CARGO_BIN_FILE_KBENCH_kbench"));
 
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ArcaOptions {
+    /// Expect every call to fault.
+    ///
+    /// kbench checks that each such call ends without the guest returning a word. It can't tell
+    /// why the guest faulted, so unlike the other runtimes the faults aren't classified.
+    pub faults: bool,
+    /// What to apply the guest to, and what it must return. Payload workloads are applied to
+    /// the payload's length and a blob of the payload, and must return a blob of the output.
+    pub arguments: Arguments,
+}
+
 pub struct ArcaBenchmark {
-    elf: &'static [u8],
+    elf: Cow<'static, [u8]>,
+    options: ArcaOptions,
 }
 
 impl ArcaBenchmark {
-    pub fn new(elf: &'static [u8]) -> Self {
-        ArcaBenchmark { elf }
+    pub fn new(elf: impl Into<Cow<'static, [u8]>>, options: ArcaOptions) -> Self {
+        ArcaBenchmark {
+            elf: elf.into(),
+            options,
+        }
+    }
+}
+
+fn read<const N: usize>(elf: &[u8], offset: u64) -> Result<[u8; N]> {
+    let bytes = usize::try_from(offset)
+        .ok()
+        .and_then(|offset| elf.get(offset..offset.checked_add(N)?))
+        .context("truncated ELF file")?;
+    Ok(bytes.try_into().unwrap())
+}
+
+fn read_u16(elf: &[u8], offset: u64) -> Result<u16> {
+    Ok(u16::from_le_bytes(read(elf, offset)?))
+}
+
+fn read_u32(elf: &[u8], offset: u64) -> Result<u32> {
+    Ok(u32::from_le_bytes(read(elf, offset)?))
+}
+
+fn read_u64(elf: &[u8], offset: u64) -> Result<u64> {
+    Ok(u64::from_le_bytes(read(elf, offset)?))
+}
+
+fn check_elf(elf: &[u8]) -> Result<()> {
+    anyhow::ensure!(
+        elf.starts_with(b"\x7fELF\x02\x01"),
+        "not a 64-bit little-endian ELF file"
+    );
+    Ok(())
+}
+
+/// The offset of `symbol`'s entry in the symbol table.
+fn find_symbol(elf: &[u8], symbol: &str) -> Result<u64> {
+    const SHT_SYMTAB: u32 = 2;
+    let section_offset = read_u64(elf, 0x28)?;
+    let section_size = read_u16(elf, 0x3a)? as u64;
+    let sections = read_u16(elf, 0x3c)? as u64;
+    let section = |index: u64| section_offset + index * section_size;
+
+    for symtab in (0..sections).map(section) {
+        if read_u32(elf, symtab + 4)? != SHT_SYMTAB {
+            continue;
+        }
+        let symbols = read_u64(elf, symtab + 24)?;
+        let symbols_size = read_u64(elf, symtab + 32)?;
+        let symbol_size = read_u64(elf, symtab + 56)?;
+        let strtab = section(read_u32(elf, symtab + 40)? as u64);
+        let names = read_u64(elf, strtab + 24)?;
+        for entry in (symbols..symbols + symbols_size).step_by(symbol_size.max(1) as usize) {
+            let name = names + read_u32(elf, entry)? as u64;
+            let name = elf
+                .get(name as usize..)
+                .and_then(|name| name.split(|&byte| byte == 0).next())
+                .context("truncated ELF file")?;
+            if name == symbol.as_bytes() {
+                return Ok(entry);
+            }
+        }
+    }
+    anyhow::bail!("no symbol `{symbol}` in ELF file")
+}
+
+/// Copy a 64-bit little-endian ELF executable, setting the `usize` static `symbol` to `value`.
+///
+/// This lets a single user binary be built for a parameter chosen at run time, as long as the
+/// binary reads the static in a way the compiler can't fold (such as with `read_volatile`).
+pub fn patch_static(elf: &[u8], symbol: &str, value: usize) -> Result<Vec<u8>> {
+    const SHT_NOBITS: u32 = 8;
+    check_elf(elf)?;
+    let section_offset = read_u64(elf, 0x28)?;
+    let section_size = read_u16(elf, 0x3a)? as u64;
+    let entry = find_symbol(elf, symbol)?;
+    anyhow::ensure!(
+        read_u64(elf, entry + 16)? == size_of::<usize>() as u64,
+        "`{symbol}` isn't a usize"
+    );
+    let address = read_u64(elf, entry + 8)?;
+    let containing = section_offset + read_u16(elf, entry + 6)? as u64 * section_size;
+    anyhow::ensure!(
+        read_u32(elf, containing + 4)? != SHT_NOBITS,
+        "`{symbol}` is zero-initialised and mutable, so it isn't stored in the file"
+    );
+    let section_address = read_u64(elf, containing + 16)?;
+    let section_file_offset = read_u64(elf, containing + 24)?;
+    let offset = address
+        .checked_sub(section_address)
+        .context("symbol outside its section")?
+        + section_file_offset;
+    read::<8>(elf, offset)?;
+    let mut patched = elf.to_vec();
+    patched[offset as usize..][..8].copy_from_slice(&value.to_le_bytes());
+    Ok(patched)
+}
+
+/// Copy a 64-bit little-endian ELF executable, giving it `size` bytes of zeroed memory starting
+/// at the first page boundary after its `_end` symbol, and setting its `usize` static
+/// `MEMORY_SIZE` to `size`.
+///
+/// The memory is added to the end of the highest loadable segment, which `_end` must end, so a
+/// user binary's memory can be sized for a parameter chosen at run time rather than by a static
+/// array as large as the largest it could need. Its pages are zero until the binary touches them.
+pub fn reserve_memory(elf: &[u8], size: usize) -> Result<Vec<u8>> {
+    const PT_LOAD: u32 = 1;
+    const PF_W: u32 = 2;
+    const PAGE: u64 = 4096;
+    let mut patched = patch_static(elf, "MEMORY_SIZE", size)?;
+    let end = read_u64(elf, find_symbol(elf, "_end")? + 8)?;
+    let headers = read_u64(elf, 0x20)?;
+    let header_size = read_u16(elf, 0x36)? as u64;
+    let count = read_u16(elf, 0x38)? as u64;
+    let mut last = None;
+    for header in (0..count).map(|index| headers + index * header_size) {
+        if read_u32(elf, header)? != PT_LOAD {
+            continue;
+        }
+        let address = read_u64(elf, header + 16)?;
+        if last.is_none_or(|(_, highest)| address > highest) {
+            last = Some((header, address));
+        }
     }
+    let (header, address) = last.context("no loadable segment")?;
+    let memory_size = read_u64(elf, header + 40)?;
+    anyhow::ensure!(
+        address + memory_size == end,
+        "`_end` isn't the end of the last segment"
+    );
+    anyhow::ensure!(
+        read_u32(elf, header + 4)? & PF_W != 0,
+        "the last segment isn't writable"
+    );
+    let memory_size = end.next_multiple_of(PAGE) - address + size as u64;
+    patched[(header + 40) as usize..][..8].copy_from_slice(&memory_size.to_le_bytes());
+    Ok(patched)
 }
 
 impl Benchmark for ArcaBenchmark {
-    fn bench(&self, parallel: usize, warmup: Duration, duration: Duration) -> Vec<usize> {
+    fn bench(&self, parallel: usize, warmup: Duration, duration: Duration) -> Result<Vec<usize>> {
         let mut mmap = Mmap::new(1 << 32);
         let cpus: usize = std::thread::available_parallelism().unwrap().into();
         let runtime = Runtime::new(cpus, &mut mmap, KERNEL_ELF.into());
@@ -36,17 +187,157 @@ impl Benchmark for ArcaBenchmark {
             let out_length = inner.len();
             assert_eq!(out_length, parallel);
             let mut new_elf = Vec::with_capacity_in(self.elf.len(), allocator);
-            new_elf.extend_from_slice(self.elf);
+            new_elf.extend_from_slice(&self.elf);
             let new_elf = new_elf.into_boxed_slice();
             let ptr = new_elf.as_ptr();
             let len = new_elf.len();
             let offset = allocator.to_offset(ptr);
             let duration = duration.as_nanos().try_into().unwrap();
             let warmup = warmup.as_nanos().try_into().unwrap();
-            runtime.run(&[offset, len, warmup, duration, out_offset, out_length]);
+            runtime.run(&[
+                offset,
+                len,
+                warmup,
+                duration,
+                out_offset,
+                out_length,
+                self.options.faults as usize,
+                self.options.arguments.lhs as usize,
+                self.options.arguments.rhs as usize,
+                self.options
+                    .arguments
+                    .expected
+                    .map_or(usize::MAX, |expected| expected as usize),
+                if self.options.arguments.payload {
+                    self.options.arguments.lhs as usize
+                } else {
+                    0
+                },
+            ]);
             output.iter().map(|x| x.load(Ordering::SeqCst)).collect()
         };
         std::mem::drop(runtime);
-        value
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PF_W: u32 = 2;
+    const PF_R: u32 = 4;
+    /// Where the test ELF's data, and its one loadable segment, are in the file and in memory.
+    const DATA: u64 = 0x80;
+    const DATA_ADDRESS: u64 = 0x10080;
+    /// Where the loadable segment's header is in the test ELF, and its memory size within that.
+    const MEMSZ: usize = 0x40 + 40;
+
+    /// A minimal ELF with one loadable segment holding the usize `MEMORY_SIZE`, the u32 `SMALL`,
+    /// and `_end` after them.
+    fn elf(flags: u32) -> Vec<u8> {
+        let strtab = b"\0MEMORY_SIZE\0SMALL\0_end\0";
+        let strtab_offset = DATA + 16;
+        let symtab_offset = (strtab_offset + strtab.len() as u64).next_multiple_of(8);
+        let symbols: [(u32, u16, u64, u64); 4] = [
+            (0, 0, 0, 0),
+            (1, 1, DATA_ADDRESS, 8),
+            (13, 1, DATA_ADDRESS + 8, 4),
+            (19, 0xfff1, DATA_ADDRESS + 16, 0),
+        ];
+        let sections_offset = symtab_offset + 24 * symbols.len() as u64;
+
+        let mut elf = b"\x7fELF\x02\x01\x01".to_vec();
+        elf.resize(16, 0);
+        elf.extend(2u16.to_le_bytes());
+        elf.extend(0x3eu16.to_le_bytes());
+        elf.extend(1u32.to_le_bytes());
+        elf.extend(DATA_ADDRESS.to_le_bytes());
+        elf.extend(0x40u64.to_le_bytes());
+        elf.extend(sections_offset.to_le_bytes());
+        elf.extend(0u32.to_le_bytes());
+        for half in [64u16, 56, 1, 64, 4, 0] {
+            elf.extend(half.to_le_bytes());
+        }
+
+        elf.extend(1u32.to_le_bytes());
+        elf.extend(flags.to_le_bytes());
+        for word in [DATA, DATA_ADDRESS, DATA_ADDRESS, 16, 16, 4096] {
+            elf.extend(word.to_le_bytes());
+        }
+
+        elf.resize(DATA as usize, 0);
+        elf.extend(0u64.to_le_bytes());
+        elf.extend(0u64.to_le_bytes());
+        elf.extend(strtab);
+        elf.resize(symtab_offset as usize, 0);
+        for (name, index, value, size) in symbols {
+            elf.extend(name.to_le_bytes());
+            elf.extend([0, 0]);
+            elf.extend(index.to_le_bytes());
+            elf.extend(value.to_le_bytes());
+            elf.extend(size.to_le_bytes());
+        }
+
+        let mut section =
+            |kind: u32, address: u64, offset: u64, size: u64, link: u32, entry: u64| {
+                elf.extend(0u32.to_le_bytes());
+                elf.extend(kind.to_le_bytes());
+                for word in [0, address, offset, size] {
+                    elf.extend(word.to_le_bytes());
+                }
+                elf.extend(link.to_le_bytes());
+                elf.extend(0u32.to_le_bytes());
+                elf.extend(8u64.to_le_bytes());
+                elf.extend(entry.to_le_bytes());
+            };
+        section(0, 0, 0, 0, 0, 0);
+        section(1, DATA_ADDRESS, DATA, 16, 0, 0);
+        section(2, 0, symtab_offset, 24 * symbols.len() as u64, 3, 24);
+        section(3, 0, strtab_offset, strtab.len() as u64, 0, 0);
+        elf
+    }
+
+    fn word(elf: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(elf[offset..][..8].try_into().unwrap())
+    }
+
+    #[test]
+    fn patch_static_sets_the_value() {
+        let elf = elf(PF_R | PF_W);
+        let patched = patch_static(&elf, "MEMORY_SIZE", 1234).unwrap();
+        assert_eq!(word(&patched, DATA as usize), 1234);
+        // Nothing but the static changes.
+        assert_eq!(patched[..DATA as usize], elf[..DATA as usize]);
+        assert_eq!(patched[DATA as usize + 8..], elf[DATA as usize + 8..]);
+    }
+
+    #[test]
+    fn reserve_memory_grows_the_last_segment() {
+        let size = 3 << 12;
+        let patched = reserve_memory(&elf(PF_R | PF_W), size).unwrap();
+        assert_eq!(word(&patched, DATA as usize), size as u64);
+        // The segment runs to the page after `_end`, then on for `size` bytes.
+        let end = (DATA_ADDRESS + 16).next_multiple_of(4096);
+        assert_eq!(word(&patched, MEMSZ), end - DATA_ADDRESS + size as u64);
+    }
+
+    #[test]
+    fn rejects_a_missing_symbol() {
+        let error = patch_static(&elf(PF_R | PF_W), "MISSING", 1).unwrap_err();
+        assert_eq!(error.to_string(), "no symbol `MISSING` in ELF file");
+        assert!(find_symbol(&elf(PF_R | PF_W), "MISSING").is_err());
+    }
+
+    #[test]
+    fn rejects_a_symbol_of_the_wrong_size() {
+        let error = patch_static(&elf(PF_R | PF_W), "SMALL", 1).unwrap_err();
+        assert_eq!(error.to_string(), "`SMALL` isn't a usize");
+    }
+
+    #[test]
+    fn rejects_a_read_only_segment() {
+        let error = reserve_memory(&elf(PF_R), 4096).unwrap_err();
+        assert_eq!(error.to_string(), "the last segment isn't writable");
     }
 }
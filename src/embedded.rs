@@ -0,0 +1,129 @@
+//! Runtimes embedded in the benchmark as libraries. They all call a workload the same way, so an
+//! [`Embedding`] only sets its engine up and says which trap a failed call hit.
+
+use anyhow::Result;
+
+use crate::{Arguments, Payload, SimpleRuntime, Trap, TrapCounter};
+
+/// An embedded runtime, with the module it runs.
+pub trait Embedding {
+    /// An instance, with its store and the exports the benchmark calls.
+    type Instance;
+    /// The error a call fails with, which may be a trap.
+    type Error: Into<anyhow::Error>;
+
+    /// Instantiate the module, which must export `add`, and for `payload` workloads also
+    /// `reserve` and its memory.
+    fn instantiate(&self, payload: bool) -> Result<Self::Instance>;
+    /// Call the instance's `add`.
+    fn add(instance: &mut Self::Instance, lhs: u32, rhs: u32) -> Result<u32, Self::Error>;
+    /// Classify a call's error by the trap that caused it.
+    fn classify(error: &Self::Error) -> Trap;
+    /// Call the instance's `reserve`.
+    fn reserve(instance: &mut Self::Instance, len: u32) -> Result<u32>;
+    /// Copy `bytes` into the instance's memory at `offset`.
+    fn write(instance: &mut Self::Instance, offset: usize, bytes: &[u8]) -> Result<()>;
+    /// Copy the instance's memory at `offset` out into `bytes`.
+    fn read(instance: &mut Self::Instance, offset: usize, bytes: &mut [u8]) -> Result<()>;
+}
+
+pub struct EmbeddedBenchmark<E> {
+    embedding: E,
+    /// Whether each thread reuses one instance, rather than instantiating one per call.
+    reuse: bool,
+    traps: Option<TrapCounter>,
+    arguments: Arguments,
+    payload: Option<Payload>,
+}
+
+impl<E: Embedding> EmbeddedBenchmark<E> {
+    /// Run `embedding`'s module, which is called with `arguments` and expected to trap on every
+    /// call if `traps` is set.
+    pub fn from_embedding(embedding: E, reuse: bool, traps: bool, arguments: Arguments) -> Self {
+        EmbeddedBenchmark {
+            embedding,
+            reuse,
+            traps: traps.then(Default::default),
+            arguments,
+            payload: Payload::for_arguments(&arguments),
+        }
+    }
+
+    /// The number of traps of each kind recovered from, if the workload is expected to trap.
+    pub fn trap_counts(&self) -> Vec<(Trap, usize)> {
+        self.traps
+            .as_ref()
+            .map(TrapCounter::counts)
+            .unwrap_or_default()
+    }
+
+    fn instantiate(&self) -> Result<E::Instance> {
+        self.embedding.instantiate(self.payload.is_some())
+    }
+
+    fn call(&self, instance: &mut E::Instance) -> Result<()> {
+        if let Some(payload) = &self.payload {
+            return transfer::<E>(instance, payload);
+        }
+        let Arguments { lhs, rhs, .. } = self.arguments;
+        let result = E::add(instance, lhs, rhs);
+        match (&self.traps, result) {
+            (None, result) => self.arguments.check(result.map_err(Into::into)?)?,
+            (Some(_), Ok(result)) => {
+                anyhow::bail!("expected a trap, but the call returned {result}")
+            }
+            (Some(traps), Err(error)) => traps.record(E::classify(&error)),
+        }
+        Ok(())
+    }
+
+    /// Check that a call which doesn't trap still succeeds after the traps.
+    fn check_recovered(&self, instance: Option<&mut E::Instance>) -> Result<()> {
+        if self.traps.is_none() {
+            return Ok(());
+        }
+        let mut fresh;
+        let instance = match instance {
+            Some(instance) => instance,
+            None => {
+                fresh = self.instantiate()?;
+                &mut fresh
+            }
+        };
+        let result = E::add(instance, 0, 2);
+        anyhow::ensure!(result.ok() == Some(2), "runtime didn't recover from traps");
+        Ok(())
+    }
+}
+
+/// Copy a payload into the instance's memory, call `add` on it, and copy the output back out.
+fn transfer<E: Embedding>(instance: &mut E::Instance, payload: &Payload) -> Result<()> {
+    let len = payload.input.len();
+    let input = E::reserve(instance, len as u32)?;
+    E::write(instance, input as usize, &payload.input)?;
+    let output = E::add(instance, len as u32, input).map_err(Into::into)?;
+    // Copy the output out, as a host passing it on would.
+    let mut copy = vec![0; len];
+    E::read(instance, output as usize, &mut copy)?;
+    payload.check(&copy)
+}
+
+impl<E: Embedding> SimpleRuntime for EmbeddedBenchmark<E> {
+    /// The thread's instance, if it reuses one.
+    type State = Option<E::Instance>;
+
+    fn setup(&self) -> Result<Self::State> {
+        self.reuse.then(|| self.instantiate()).transpose()
+    }
+
+    fn iterate(&self, state: &mut Self::State) -> Result<()> {
+        match state {
+            Some(instance) => self.call(instance),
+            None => self.call(&mut self.instantiate()?),
+        }
+    }
+
+    fn teardown(&self, mut state: Self::State) -> Result<()> {
+        self.check_recovered(state.as_mut())
+    }
+}
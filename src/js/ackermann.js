@@ -0,0 +1,14 @@
+function ackermann(m, n) {
+  if (m === 0) {
+    return (n + 1) >>> 0;
+  }
+  if (n === 0) {
+    return ackermann(m - 1, 1);
+  }
+  return ackermann(m - 1, ackermann(m, n - 1));
+}
+
+// Returns A(2, lhs), whose calls nest about 2 * lhs deep.
+function add(lhs, rhs) {
+  return ackermann(2, lhs);
+}
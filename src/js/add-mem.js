@@ -0,0 +1,7 @@
+function add(lhs, rhs) {
+  const memory = new Int32Array(65536 / 4);
+  memory[0] = lhs;
+  memory[1] = rhs;
+  memory[2] = memory[0] + memory[1];
+  return memory[2];
+}
@@ -0,0 +1,13 @@
+function add(lhs, rhs) {
+  const memory = new Int32Array(65536 / 4);
+  for (let i = 0; i < 4096; i++) {
+    memory[i] = lhs;
+  }
+  for (let i = 0; i < 4096; i++) {
+    memory[4096 + i] = rhs;
+  }
+  for (let i = 0; i < 4096; i++) {
+    memory[8192 + i] = memory[i] + memory[4096 + i];
+  }
+  return memory[8192];
+}
@@ -0,0 +1,9 @@
+// The nth Fibonacci number (wrapping), computed with exponentially many calls.
+function fib(n) {
+  return n < 2 ? n : (fib(n - 1) + fib(n - 2)) >>> 0;
+}
+
+// Returns fib(lhs).
+function add(lhs, rhs) {
+  return fib(lhs);
+}
@@ -0,0 +1,23 @@
+// Multiplies a 64x64 f32 matrix filled with lhs by one filled with rhs and returns the sum of
+// the product, truncated to an integer. Every value is an integer below 2^24, so it's exact.
+function add(lhs, rhs) {
+  const memory = new Float32Array(64 * 64 * 3);
+  memory.fill(lhs, 0, 4096);
+  memory.fill(rhs, 4096, 8192);
+
+  for (let y = 0; y < 64; y++) {
+    for (let x = 0; x < 64; x++) {
+      let sum = 0;
+      for (let i = 0; i < 64; i++) {
+        sum = Math.fround(sum + Math.fround(memory[64 * y + i] * memory[4096 + 64 * i + x]));
+      }
+      memory[8192 + 64 * y + x] = sum;
+    }
+  }
+
+  let sum = 0;
+  for (let i = 8192; i < 12288; i++) {
+    sum = Math.fround(sum + memory[i]);
+  }
+  return Math.trunc(sum) | 0;
+}
@@ -0,0 +1,11 @@
+// Grows a buffer to lhs MiB, writes to every 4 KiB page of it, and returns lhs + rhs.
+function add(lhs, rhs) {
+  const size = lhs * (1 << 20);
+  const buffer = new ArrayBuffer(65536, { maxByteLength: size });
+  buffer.resize(size);
+  const memory = new Uint8Array(buffer);
+  for (let i = 0; i < size; i += 4096) {
+    memory[i] = lhs;
+  }
+  return (lhs + rhs) | 0;
+}
@@ -0,0 +1,8 @@
+// Calls the host lhs times, adding rhs each time, and returns lhs * rhs.
+function add(lhs, rhs) {
+  let acc = 0;
+  for (let i = 0; i < lhs; i++) {
+    acc = host.add(acc, rhs);
+  }
+  return acc;
+}
@@ -0,0 +1,18 @@
+// An instruction takes the accumulator and the step number and returns the new accumulator.
+// `var` rather than `const`, since the script is re-run in the same context.
+var INSTRUCTIONS = [
+  (acc, i) => (acc + i) >>> 0,
+  (acc, i) => Math.imul(acc, 3) >>> 0,
+  (acc, i) => (acc ^ i) >>> 0,
+  (acc, i) => acc >>> 1,
+];
+
+// Runs lhs steps, each calling the instruction picked by the accumulator and step number, and
+// returns the accumulator.
+function add(lhs, rhs) {
+  let acc = 0;
+  for (let i = 0; i < lhs; i++) {
+    acc = INSTRUCTIONS[(acc ^ i) & 3](acc, i);
+  }
+  return acc;
+}
@@ -0,0 +1,3 @@
+function add(lhs, rhs) {
+  for (;;) {}
+}
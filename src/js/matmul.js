@@ -0,0 +1,45 @@
+// A template for `benchmark::matmul::js`, which fills in the dimension.
+// `var` rather than `const`, since the script is re-run in the same context.
+var DIM = {{DIM}};
+
+function set(memory, idx, x, y, val) {
+  memory[DIM * DIM * idx + DIM * y + x] = val;
+}
+
+function get(memory, idx, x, y) {
+  return memory[DIM * DIM * idx + DIM * y + x];
+}
+
+function add(lhs, rhs) {
+  const memory = new Int32Array(DIM * DIM * 3);
+
+  for (let y = 0; y < DIM; y++) {
+    for (let x = 0; x < DIM; x++) {
+      set(memory, 0, x, y, lhs);
+    }
+  }
+
+  for (let y = 0; y < DIM; y++) {
+    for (let x = 0; x < DIM; x++) {
+      set(memory, 1, x, y, rhs);
+    }
+  }
+
+  for (let y = 0; y < DIM; y++) {
+    for (let x = 0; x < DIM; x++) {
+      let sum = 0;
+      for (let i = 0; i < DIM; i++) {
+        sum = (sum + Math.imul(get(memory, 0, i, y), get(memory, 1, x, i))) | 0;
+      }
+      set(memory, 2, x, y, sum);
+    }
+  }
+
+  let sum = 0;
+  for (let y = 0; y < DIM; y++) {
+    for (let x = 0; x < DIM; x++) {
+      sum = (sum + get(memory, 2, x, y)) | 0;
+    }
+  }
+  return sum;
+}
@@ -0,0 +1,66 @@
+// The five bodies of the benchmarks game n-body simulation, eight f64s each: the position, a
+// pad, the velocity and the mass. The sun's velocity offsets the planets' momentum. `var` rather
+// than `const`, since the script is re-run in the same context.
+var BODIES = [
+  // Sun
+  0, 0, 0, 0, -0.00038766340719874267, -0.0032753590371765707, 2.3935734080003e-05, 39.47841760435743,
+  // Jupiter
+  4.841431442464721, -1.1603200440274284, -0.10362204447112311, 0, 0.606326392995832, 2.81198684491626, -0.02521836165988763, 0.03769367487038949,
+  // Saturn
+  8.34336671824458, 4.124798564124305, -0.4035234171143214, 0, -1.0107743461787924, 1.8256623712304119, 0.008415761376584154, 0.011286326131968767,
+  // Uranus
+  12.894369562139131, -15.111151401698631, -0.22330757889265573, 0, 1.0827910064415354, 0.8687130181696082, -0.010832637401363636, 0.0017237240570597112,
+  // Neptune
+  15.379697114850917, -25.919314609987964, 0.17925877295037118, 0, 0.979090732243898, 0.5946989986476762, -0.034755955504078104, 0.0020336868699246304,
+];
+
+function advance(bodies) {
+  for (let i = 0; i < 40; i += 8) {
+    for (let j = i + 8; j < 40; j += 8) {
+      const dx = bodies[i] - bodies[j];
+      const dy = bodies[i + 1] - bodies[j + 1];
+      const dz = bodies[i + 2] - bodies[j + 2];
+      const d2 = dx * dx + dy * dy + dz * dz;
+      const mag = 0.01 / (d2 * Math.sqrt(d2));
+      const mi = bodies[i + 7] * mag;
+      const mj = bodies[j + 7] * mag;
+      bodies[i + 4] -= dx * mj;
+      bodies[i + 5] -= dy * mj;
+      bodies[i + 6] -= dz * mj;
+      bodies[j + 4] += dx * mi;
+      bodies[j + 5] += dy * mi;
+      bodies[j + 6] += dz * mi;
+    }
+  }
+  for (let i = 0; i < 40; i += 8) {
+    bodies[i] += 0.01 * bodies[i + 4];
+    bodies[i + 1] += 0.01 * bodies[i + 5];
+    bodies[i + 2] += 0.01 * bodies[i + 6];
+  }
+}
+
+function energy(bodies) {
+  let e = 0;
+  for (let i = 0; i < 40; i += 8) {
+    const vx = bodies[i + 4];
+    const vy = bodies[i + 5];
+    const vz = bodies[i + 6];
+    e += 0.5 * bodies[i + 7] * (vx * vx + vy * vy + vz * vz);
+    for (let j = i + 8; j < 40; j += 8) {
+      const dx = bodies[i] - bodies[j];
+      const dy = bodies[i + 1] - bodies[j + 1];
+      const dz = bodies[i + 2] - bodies[j + 2];
+      e -= (bodies[i + 7] * bodies[j + 7]) / Math.sqrt(dx * dx + dy * dy + dz * dz);
+    }
+  }
+  return e;
+}
+
+// Runs the simulation for lhs steps and returns the energy in nanojoules, truncated.
+function add(lhs, rhs) {
+  const bodies = new Float64Array(BODIES);
+  for (let step = 0; step < lhs; step++) {
+    advance(bodies);
+  }
+  return Math.trunc(energy(bodies) * 1e9) | 0;
+}
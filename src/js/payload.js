@@ -0,0 +1,9 @@
+// Returns a copy of the input ArrayBuffer with every byte inverted.
+function add(input) {
+  const bytes = new Uint8Array(input);
+  const output = new Uint8Array(bytes.length);
+  for (let i = 0; i < bytes.length; i++) {
+    output[i] = ~bytes[i];
+  }
+  return output.buffer;
+}
@@ -0,0 +1,7 @@
+// Divides rhs by zero unless lhs is 0, in which case it returns rhs. Only BigInt division throws.
+function add(lhs, rhs) {
+  if (lhs === 0) {
+    return rhs;
+  }
+  return Number(BigInt(rhs) / BigInt(lhs - lhs));
+}
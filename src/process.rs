@@ -0,0 +1,441 @@
+//! Baselines isolating calls in Linux processes, as traditional sandboxes do. Every call can get a
+//! process of its own, either forked from the benchmark, which has already set the workload up,
+//! or spawned afresh from the benchmark's own executable; or calls can be dispatched to a pool of
+//! forked workers, which serve many calls each. Either way the worker runs the workload natively,
+//! takes its arguments (and any payload) over a pipe or socket, and sends its result back.
+
+use std::{
+    ffi::OsString,
+    io::{self, Read, Write},
+    net::Shutdown,
+    os::unix::{net::UnixStream, process::ExitStatusExt},
+    panic::AssertUnwindSafe,
+    path::PathBuf,
+    process::{Command, ExitStatus, Stdio},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    Arguments, Payload, SimpleRuntime,
+    native::{Memory, Workload},
+};
+
+/// How a worker process is started for each call.
+pub enum Launch {
+    /// Fork the benchmark, so the worker starts with the workload and `usize` bytes of memory for
+    /// it ready.
+    Fork(Workload, usize),
+    /// Spawn `program` with `args`, which should answer one request with [`serve`].
+    Spawn {
+        program: PathBuf,
+        args: Vec<OsString>,
+    },
+}
+
+/// The request every call sends a worker, and what it checks the response against.
+struct Call {
+    arguments: Arguments,
+    payload: Option<Payload>,
+    /// The arguments, then any payload input.
+    request: Vec<u8>,
+}
+
+impl Call {
+    fn new(arguments: Arguments) -> Self {
+        let payload = Payload::for_arguments(&arguments);
+        let mut request = [arguments.lhs, arguments.rhs]
+            .map(u32::to_le_bytes)
+            .concat();
+        if let Some(payload) = &payload {
+            request.extend_from_slice(&payload.input);
+        }
+        Call {
+            arguments,
+            payload,
+            request,
+        }
+    }
+
+    /// Check a response: the result, or the payload output.
+    fn check(&self, response: &[u8]) -> Result<()> {
+        match &self.payload {
+            Some(payload) => payload.check(response),
+            None => {
+                let result = response
+                    .try_into()
+                    .context("worker sent a malformed result")?;
+                self.arguments.check(u32::from_le_bytes(result))
+            }
+        }
+    }
+}
+
+pub struct ProcessBenchmark {
+    launch: Launch,
+    call: Call,
+}
+
+impl ProcessBenchmark {
+    pub fn new(launch: Launch, arguments: Arguments) -> Self {
+        ProcessBenchmark {
+            launch,
+            call: Call::new(arguments),
+        }
+    }
+}
+
+impl SimpleRuntime for ProcessBenchmark {
+    /// The memory forked workers inherit, which they write to copies of.
+    type State = Memory;
+
+    fn setup(&self) -> Result<Self::State> {
+        Ok(match self.launch {
+            Launch::Fork(_, memory) => Memory::new(memory),
+            Launch::Spawn { .. } => Memory::new(0),
+        })
+    }
+
+    fn iterate(&self, memory: &mut Self::State) -> Result<()> {
+        let request = &self.call.request;
+        let response = match &self.launch {
+            Launch::Fork(workload, _) => fork(workload, memory.bytes(), request),
+            Launch::Spawn { program, args } => spawn(program, args, request),
+        }
+        .context("failed to run a worker")?;
+        self.call.check(&response)
+    }
+}
+
+/// How a [`PoolBenchmark`] keeps its workers.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolOptions {
+    /// How many workers each benchmark thread keeps, and dispatches calls to in turn.
+    pub workers: usize,
+    /// How many calls a worker serves before it's replaced with a fresh fork, if it ever is.
+    pub recycle: Option<u32>,
+}
+
+pub struct PoolBenchmark {
+    workload: Workload,
+    memory: usize,
+    options: PoolOptions,
+    call: Call,
+}
+
+impl PoolBenchmark {
+    /// Dispatch calls to workers forked from the benchmark with `workload` and `memory` bytes of
+    /// memory for it ready, which they reuse across calls.
+    pub fn new(
+        workload: Workload,
+        memory: usize,
+        options: PoolOptions,
+        arguments: Arguments,
+    ) -> Self {
+        assert!(options.workers > 0, "a pool needs at least one worker");
+        PoolBenchmark {
+            workload,
+            memory,
+            options,
+            call: Call::new(arguments),
+        }
+    }
+
+    fn worker(&self, memory: &mut [u8]) -> Result<Worker> {
+        Worker::fork(&self.workload, memory).context("failed to fork a worker")
+    }
+}
+
+/// A benchmark thread's workers.
+pub struct Pool {
+    workers: Vec<Worker>,
+    next: usize,
+    /// The memory new workers inherit.
+    memory: Memory,
+    response: Vec<u8>,
+}
+
+impl SimpleRuntime for PoolBenchmark {
+    type State = Pool;
+
+    fn setup(&self) -> Result<Self::State> {
+        let mut memory = Memory::new(self.memory);
+        let workers = (0..self.options.workers)
+            .map(|_| self.worker(memory.bytes()))
+            .collect::<Result<_>>()?;
+        Ok(Pool {
+            workers,
+            next: 0,
+            memory,
+            response: vec![],
+        })
+    }
+
+    fn iterate(&self, pool: &mut Self::State) -> Result<()> {
+        let index = pool.next;
+        pool.next = (index + 1) % pool.workers.len();
+        if self
+            .options
+            .recycle
+            .is_some_and(|recycle| pool.workers[index].served >= recycle)
+        {
+            // Dropping the old worker waits for it to exit.
+            pool.workers[index] = self.worker(pool.memory.bytes())?;
+        }
+        pool.workers[index]
+            .call(&self.call.request, &mut pool.response)
+            .context("failed to call a worker")?;
+        self.call.check(&pool.response)
+    }
+}
+
+/// A forked worker serving calls over a socket until it's closed.
+struct Worker {
+    pid: libc::pid_t,
+    socket: UnixStream,
+    served: u32,
+}
+
+impl Worker {
+    fn fork(workload: &Workload, memory: &mut [u8]) -> io::Result<Self> {
+        let (socket, theirs) = UnixStream::pair()?;
+        let pid = fork_with(|| serve_stream(workload, memory, theirs))?;
+        Ok(Worker {
+            pid,
+            socket,
+            served: 0,
+        })
+    }
+
+    fn call(&mut self, request: &[u8], response: &mut Vec<u8>) -> io::Result<()> {
+        send(&mut self.socket, request)?;
+        if !receive(&mut self.socket, response)? {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "worker exited",
+            ));
+        }
+        self.served += 1;
+        Ok(())
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // Shut the socket down rather than just closing it, as workers forked by other threads
+        // inherit it too, and the worker would never see it close.
+        let _ = self.socket.shutdown(Shutdown::Both);
+        let _ = wait(self.pid);
+    }
+}
+
+/// Answer calls read from `socket` until it's shut down.
+fn serve_stream(workload: &Workload, memory: &mut [u8], mut socket: UnixStream) -> io::Result<()> {
+    let mut request = vec![];
+    let mut response = vec![];
+    while receive(&mut socket, &mut request)? {
+        response.clear();
+        answer(workload, memory, &request, &mut response)?;
+        send(&mut socket, &response)?;
+    }
+    Ok(())
+}
+
+/// Send a message prefixed with its length.
+fn send(mut stream: impl Write, message: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(message.len()).map_err(io::Error::other)?;
+    stream.write_all(&len.to_le_bytes())?;
+    stream.write_all(message)
+}
+
+/// Receive a message sent with [`send`], or `false` if the stream was closed or shut down.
+fn receive(mut stream: impl Read, message: &mut Vec<u8>) -> io::Result<bool> {
+    let mut len = [0; 4];
+    match stream.read_exact(&mut len) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(false),
+        result => result?,
+    }
+    message.resize(u32::from_le_bytes(len) as usize, 0);
+    stream.read_exact(message)?;
+    Ok(true)
+}
+
+/// Run `f` in a forked child, which exits once it returns, and return the child's pid.
+fn fork_with(f: impl FnOnce() -> io::Result<()>) -> io::Result<libc::pid_t> {
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        0 => {
+            // Never return (or unwind) into the benchmark's threads, which don't exist here.
+            let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+            let status = if let Ok(Ok(())) = result { 0 } else { 1 };
+            unsafe { libc::_exit(status) }
+        }
+        pid => Ok(pid),
+    }
+}
+
+fn wait(pid: libc::pid_t) -> io::Result<ExitStatus> {
+    let mut status = 0;
+    if unsafe { libc::waitpid(pid, &mut status, 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(ExitStatus::from_raw(status))
+}
+
+/// Answer one request in a forked child, returning its response.
+fn fork(workload: &Workload, memory: &mut [u8], request: &[u8]) -> io::Result<Vec<u8>> {
+    // Children forked by other threads at the same time inherit this pipe too, so rather than
+    // reading it to the end, which would wait for them, read just the one response.
+    let (reader, writer) = io::pipe()?;
+    let pid = fork_with(|| {
+        let mut response = vec![];
+        answer(workload, memory, request, &mut response)?;
+        send(&writer, &response)
+    })?;
+    drop(writer);
+    let mut response = vec![];
+    let received = receive(reader, &mut response);
+    check_status(wait(pid)?)?;
+    if !received? {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "worker exited",
+        ));
+    }
+    Ok(response)
+}
+
+/// Answer one request in a freshly spawned worker, returning its response.
+fn spawn(program: &PathBuf, args: &[OsString], request: &[u8]) -> io::Result<Vec<u8>> {
+    // With no `pre_exec`, this uses `posix_spawn`.
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    // The worker reads all of its request before it replies, so this can't deadlock.
+    let written = child.stdin.take().unwrap().write_all(request);
+    let output = child.wait_with_output()?;
+    written?;
+    check_status(output.status)?;
+    Ok(output.stdout)
+}
+
+fn check_status(status: ExitStatus) -> io::Result<()> {
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("worker failed ({status})")))
+    }
+}
+
+/// Run `workload` on a request (the arguments, then any payload input) and write its response
+/// (the result, or the payload output).
+fn answer(
+    workload: &Workload,
+    memory: &mut [u8],
+    request: &[u8],
+    mut response: impl Write,
+) -> io::Result<()> {
+    let malformed = || io::Error::new(io::ErrorKind::InvalidData, "malformed request");
+    let (lhs, request) = request.split_first_chunk().ok_or_else(malformed)?;
+    let (rhs, input) = request.split_first_chunk().ok_or_else(malformed)?;
+    match workload {
+        Workload::Call(f) => {
+            let result = f(u32::from_le_bytes(*lhs), u32::from_le_bytes(*rhs), memory);
+            response.write_all(&result.to_le_bytes())
+        }
+        Workload::Payload(f) => {
+            let output = memory.get_mut(..input.len()).ok_or_else(malformed)?;
+            f(input, output);
+            response.write_all(output)
+        }
+    }
+}
+
+/// Answer one request from a [`Launch::Spawn`] benchmark, read from stdin, on stdout. With
+/// `seccomp`, the worker gives up every system call it doesn't need before reading it.
+pub fn serve(workload: Workload, memory: usize, seccomp: bool) -> io::Result<()> {
+    let mut memory = Memory::new(memory);
+    if seccomp {
+        restrict()?;
+    }
+    let mut request = vec![];
+    io::stdin().lock().read_to_end(&mut request)?;
+    let mut stdout = io::stdout().lock();
+    answer(&workload, memory.bytes(), &request, &mut stdout)?;
+    stdout.flush()
+}
+
+/// The system calls a sandboxed worker may make: enough to manage its memory, answer its request
+/// and exit.
+const ALLOWED: &[libc::c_long] = &[
+    libc::SYS_read,
+    libc::SYS_write,
+    libc::SYS_close,
+    libc::SYS_fstat,
+    libc::SYS_newfstatat,
+    libc::SYS_statx,
+    libc::SYS_lseek,
+    libc::SYS_brk,
+    libc::SYS_mmap,
+    libc::SYS_munmap,
+    libc::SYS_mremap,
+    libc::SYS_mprotect,
+    libc::SYS_madvise,
+    libc::SYS_futex,
+    libc::SYS_sigaltstack,
+    libc::SYS_rt_sigaction,
+    libc::SYS_rt_sigprocmask,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+];
+
+/// Install a seccomp filter killing the process if it makes any system call not in [`ALLOWED`].
+fn restrict() -> io::Result<()> {
+    fn statement(code: u32, k: u32) -> libc::sock_filter {
+        jump(code, k, 0, 0)
+    }
+    fn jump(code: u32, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter {
+            code: code as u16,
+            jt,
+            jf,
+            k,
+        }
+    }
+    // The offsets of `arch` and `nr` in `struct seccomp_data`.
+    const ARCH: u32 = 4;
+    const NR: u32 = 0;
+    // `AUDIT_ARCH_X86_64`, from `linux/audit.h`.
+    const X86_64: u32 = 0xc000_003e;
+    let load = libc::BPF_LD | libc::BPF_W | libc::BPF_ABS;
+    let equals = libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K;
+    let ret = libc::BPF_RET | libc::BPF_K;
+
+    // System call numbers differ between architectures, so only allow the one they're for.
+    let mut filter = vec![
+        statement(load, ARCH),
+        jump(equals, X86_64, 1, 0),
+        statement(ret, libc::SECCOMP_RET_KILL_PROCESS),
+        statement(load, NR),
+    ];
+    for &nr in ALLOWED {
+        filter.push(jump(equals, nr as u32, 0, 1));
+        filter.push(statement(ret, libc::SECCOMP_RET_ALLOW));
+    }
+    filter.push(statement(ret, libc::SECCOMP_RET_KILL_PROCESS));
+    let program = libc::sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_mut_ptr(),
+    };
+    unsafe {
+        if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) == -1
+            || libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER, &program) == -1
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
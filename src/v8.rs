@@ -1,23 +1,72 @@
 use std::{
+    cell::Cell,
     marker::PhantomData,
     sync::{
-        LazyLock,
+        OnceLock,
         atomic::{AtomicUsize, Ordering},
     },
     time::{Duration, Instant},
 };
 
 use anyhow::Result;
-use v8::{CompiledWasmModule, Local, Object, WasmModuleObject};
+use v8::{CompiledWasmModule, Function, Global, Local, Object, UnboundScript, WasmModuleObject};
 
-use crate::{SimpleRuntime, SingleThreadedRuntime};
+use crate::{
+    Arguments, Arrival, Payload, SimpleRuntime, SingleThreadedRuntime, Trap, TrapCounter,
+    wasm_binary,
+};
+
+mod stats;
+mod watchdog;
 
-static ONE_TIME_INIT: LazyLock<()> = LazyLock::new(|| {
-    let platform = v8::new_default_platform(0, false).make_shared();
-    v8::V8::set_flags_from_command_line(vec!["--liftoff".into(), "--no-wasm-tier-up".into()]);
-    v8::V8::initialize_platform(platform);
-    v8::V8::initialize();
-});
+pub use stats::V8Stats;
+use stats::{Recorder, ThreadStats};
+use watchdog::Watchdog;
+
+/// How V8's platform is set up; this can only be done once per process.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct PlatformOptions {
+    /// Number of platform worker threads (0 lets V8 choose).
+    pub thread_pool_size: u32,
+    /// Whether the platform runs idle tasks.
+    pub idle_task_support: bool,
+    /// Use a single-threaded platform, so that V8 runs no background threads at all.
+    pub single_threaded: bool,
+}
+
+static PLATFORM: OnceLock<(PlatformOptions, JsCompile)> = OnceLock::new();
+
+/// Set up V8's platform and flags. The flags depend on how JavaScript workloads are compiled, so
+/// this is also fixed for the process once called.
+fn initialize(options: PlatformOptions, js_compile: JsCompile) {
+    let initialized = PLATFORM.get_or_init(|| {
+        let platform = if options.single_threaded {
+            v8::new_single_threaded_default_platform(options.idle_task_support)
+        } else {
+            v8::new_default_platform(options.thread_pool_size, options.idle_task_support)
+        }
+        .make_shared();
+        let mut flags = vec!["--liftoff".into(), "--no-wasm-tier-up".into()];
+        if options.single_threaded {
+            flags.push("--single-threaded".into());
+        }
+        if js_compile == JsCompile::PerCall {
+            // Otherwise V8 serves every compile after the first from its compilation cache, which
+            // is keyed by the source and origin.
+            flags.push("--no-compilation-cache".into());
+        }
+        v8::V8::set_flags_from_command_line(flags);
+        v8::V8::initialize_platform(platform);
+        v8::V8::initialize();
+        (options, js_compile)
+    });
+    if *initialized != (options, js_compile) {
+        log::warn!(
+            "V8 was already initialized with {initialized:?}; ignoring {:?}",
+            (options, js_compile)
+        );
+    }
+}
 
 fn compile(module: &[u8]) -> CompiledWasmModule {
     let isolate = &mut v8::Isolate::new(Default::default());
@@ -29,6 +78,100 @@ fn compile(module: &[u8]) -> CompiledWasmModule {
         .get_compiled_module()
 }
 
+fn js_source(
+    scope: &mut v8::HandleScope,
+    source: &str,
+    module: bool,
+    code_cache: Option<&[u8]>,
+) -> v8::script_compiler::Source {
+    let name = v8::String::new(scope, "workload.js").unwrap();
+    let origin = v8::ScriptOrigin::new(
+        scope,
+        name.into(),
+        0,
+        0,
+        false,
+        0,
+        None,
+        false,
+        false,
+        module,
+        None,
+    );
+    let source = v8::String::new(scope, source).unwrap();
+    match code_cache {
+        Some(data) => v8::script_compiler::Source::new_with_cached_data(
+            source,
+            Some(&origin),
+            v8::script_compiler::CachedData::new(data),
+        ),
+        None => v8::script_compiler::Source::new(source, Some(&origin)),
+    }
+}
+
+fn compile_options(code_cache: Option<&[u8]>) -> v8::script_compiler::CompileOptions {
+    match code_cache {
+        Some(_) => v8::script_compiler::CompileOptions::ConsumeCodeCache,
+        None => v8::script_compiler::CompileOptions::NoCompileOptions,
+    }
+}
+
+fn compile_script<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    source: &str,
+    code_cache: Option<&[u8]>,
+) -> Local<'s, UnboundScript> {
+    let mut source = js_source(scope, source, false, code_cache);
+    v8::script_compiler::compile_unbound_script(
+        scope,
+        &mut source,
+        compile_options(code_cache),
+        v8::script_compiler::NoCacheReason::NoReason,
+    )
+    .expect("could not compile script")
+}
+
+fn compile_module<'s>(
+    scope: &mut v8::HandleScope<'s>,
+    source: &str,
+    code_cache: Option<&[u8]>,
+) -> Local<'s, v8::Module> {
+    let mut source = js_source(scope, source, true, code_cache);
+    v8::script_compiler::compile_module2(
+        scope,
+        &mut source,
+        compile_options(code_cache),
+        v8::script_compiler::NoCacheReason::NoReason,
+    )
+    .expect("could not compile module")
+}
+
+/// Compile JavaScript once and return V8's code cache for it.
+fn code_cache(source: &str, module: bool) -> Box<[u8]> {
+    let isolate = &mut v8::Isolate::new(Default::default());
+    let scope = &mut v8::HandleScope::new(isolate);
+    let context = v8::Context::new(scope, Default::default());
+    let scope = &mut v8::ContextScope::new(scope, context);
+    let cache = if module {
+        compile_module(scope, source, None)
+            .get_unbound_module_script(scope)
+            .create_code_cache()
+    } else {
+        compile_script(scope, source, None).create_code_cache()
+    };
+    cache.expect("could not create code cache").to_vec().into()
+}
+
+fn resolve<'s>(
+    _context: Local<'s, v8::Context>,
+    _specifier: Local<'s, v8::String>,
+    _attributes: Local<'s, v8::FixedArray>,
+    _referrer: Local<'s, v8::Module>,
+) -> Option<Local<'s, v8::Module>> {
+    // Workloads are self-contained.
+    None
+}
+
 pub trait V8Mode {}
 pub struct SameIsolateSameContext;
 impl V8Mode for SameIsolateSameContext {}
@@ -37,56 +180,583 @@ impl V8Mode for SameIsolateNewContext {}
 pub struct NewIsolate;
 impl V8Mode for NewIsolate {}
 
+/// A workload which can be run by V8.
+#[derive(Debug, Copy, Clone)]
+pub enum Source<'a> {
+    /// A WebAssembly module in text or binary format which exports an `add` function.
+    Wasm(&'a [u8]),
+    /// A classic script which defines a global `add` function.
+    Script(&'a str),
+    /// The same script, loaded as an ES module which exports `add`.
+    Module(&'a str),
+}
+
+/// How JavaScript workloads are compiled.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum JsCompile {
+    /// Compile the source from scratch on every call, with V8's compilation cache disabled.
+    PerCall,
+    /// Compile scripts once per isolate into an `UnboundScript` which is bound to each context.
+    /// Modules, and scripts with an isolate per call, consume a code cache instead.
+    #[default]
+    Cached,
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct V8Options {
+    /// Collect heap statistics and GC pause times from every worker thread.
+    pub stats: bool,
+    /// Force a full GC on each worker's long-lived isolate once it has warmed up, before the
+    /// level's timed calls start. It's recorded apart from the GCs which calls cause.
+    pub gc_between_levels: bool,
+    /// How JavaScript workloads are compiled.
+    pub js_compile: JsCompile,
+    /// How V8's platform is set up.
+    pub platform: PlatformOptions,
+    /// Terminate calls which run for longer than this.
+    pub time_limit: Option<Duration>,
+    /// Allocate property names and look up `WebAssembly.Instance` on every call, rather than
+    /// using a [`PreparedCall`].
+    pub naive_calls: bool,
+    /// Expect every call to throw: catch the exceptions and count them by kind.
+    pub traps: bool,
+    /// What to call `add` with, and whether to pass it a [`Payload`].
+    pub arguments: Arguments,
+    /// Provide the host functions: to wasm modules as imports from `host`, and to JavaScript as
+    /// the global `host` object.
+    pub host: bool,
+}
+
+/// Property names used on every call, internalised once per isolate.
+struct Names {
+    webassembly: Global<v8::String>,
+    instance: Global<v8::String>,
+    exports: Global<v8::String>,
+    add: Global<v8::String>,
+    reserve: Global<v8::String>,
+    memory: Global<v8::String>,
+    buffer: Global<v8::String>,
+}
+
+fn internalize<'s>(scope: &mut v8::HandleScope<'s, ()>, name: &str) -> Local<'s, v8::String> {
+    v8::String::new_from_utf8(scope, name.as_bytes(), v8::NewStringType::Internalized).unwrap()
+}
+
+impl Names {
+    fn new(scope: &mut v8::HandleScope<()>) -> Self {
+        let mut name = |name: &str| {
+            let name = internalize(scope, name);
+            Global::new(scope, name)
+        };
+        Names {
+            webassembly: name("WebAssembly"),
+            instance: name("Instance"),
+            exports: name("exports"),
+            add: name("add"),
+            reserve: name("reserve"),
+            memory: name("memory"),
+            buffer: name("buffer"),
+        }
+    }
+}
+
+/// The handles needed to call the program, resolved once per context.
+struct PreparedCall<'s> {
+    /// The `WebAssembly.Instance` constructor, for wasm programs.
+    instance: Option<Local<'s, Function>>,
+    exports: Local<'s, v8::String>,
+    add: Local<'s, v8::String>,
+    reserve: Local<'s, v8::String>,
+    memory: Local<'s, v8::String>,
+    buffer: Local<'s, v8::String>,
+}
+
+impl<'s> PreparedCall<'s> {
+    fn new(
+        global: Local<'s, Object>,
+        scope: &mut v8::HandleScope<'s>,
+        names: Option<&Names>,
+        wasm: bool,
+    ) -> Self {
+        let [webassembly, instance, exports, add, reserve, memory, buffer] = match names {
+            Some(names) => [
+                &names.webassembly,
+                &names.instance,
+                &names.exports,
+                &names.add,
+                &names.reserve,
+                &names.memory,
+                &names.buffer,
+            ]
+            .map(|name| Local::new(scope, name)),
+            None => [
+                "WebAssembly",
+                "Instance",
+                "exports",
+                "add",
+                "reserve",
+                "memory",
+                "buffer",
+            ]
+            .map(|name| internalize(scope, name)),
+        };
+        let instance = wasm.then(|| {
+            let webassembly = global
+                .get(scope, webassembly.into())
+                .unwrap()
+                .to_object(scope)
+                .unwrap();
+            webassembly
+                .get(scope, instance.into())
+                .unwrap()
+                .to_object(scope)
+                .unwrap()
+                .cast::<Function>()
+        });
+        PreparedCall {
+            instance,
+            exports,
+            add,
+            reserve,
+            memory,
+            buffer,
+        }
+    }
+}
+
+/// Everything which can be prepared once per isolate.
+#[derive(Default)]
+struct Prepared {
+    script: Option<Global<UnboundScript>>,
+    names: Option<Names>,
+}
+
+/// The exports of a wasm instance, or the functions a JavaScript program defines, which a call
+/// uses.
+#[derive(Copy, Clone)]
+struct Exports<'s> {
+    add: Local<'s, Function>,
+    /// How to pass a [`Payload`] through a wasm instance's memory, for payload workloads.
+    memory: Option<WasmMemory<'s>>,
+}
+
+#[derive(Copy, Clone)]
+struct WasmMemory<'s> {
+    reserve: Local<'s, Function>,
+    memory: Local<'s, Object>,
+    /// The name of `WebAssembly.Memory`'s `buffer` property.
+    buffer: Local<'s, v8::String>,
+}
+
+/// Everything which can be loaded once per context.
+struct Loaded<'s> {
+    module: Option<Local<'s, WasmModuleObject>>,
+    call: Option<PreparedCall<'s>>,
+    /// The imports object to instantiate the wasm module with, if it imports host functions.
+    imports: Option<Local<'s, Object>>,
+}
+
+enum Program {
+    Wasm(CompiledWasmModule),
+    Js {
+        source: String,
+        module: bool,
+        code_cache: Option<Box<[u8]>>,
+    },
+}
+
 pub struct V8Benchmark<MODE: V8Mode> {
-    module: CompiledWasmModule,
+    program: Program,
+    options: V8Options,
+    stats: Option<Recorder>,
+    traps: Option<TrapCounter>,
+    payload: Option<Payload>,
     _phantom: PhantomData<MODE>,
 }
 
 impl<MODE: V8Mode> V8Benchmark<MODE> {
-    pub fn new(wat: &[u8]) -> Result<Self> {
-        LazyLock::force(&ONE_TIME_INIT);
+    pub fn new(source: Source, options: V8Options) -> Result<Self> {
+        initialize(options.platform, options.js_compile);
+        let cached = options.js_compile == JsCompile::Cached;
+        let program = match source {
+            Source::Wasm(module) => Program::Wasm(compile(&wasm_binary(module)?)),
+            Source::Script(source) => Program::Js {
+                source: source.into(),
+                module: false,
+                code_cache: cached.then(|| code_cache(source, false)),
+            },
+            Source::Module(source) => {
+                let source = format!("{source}\nexport {{ add }};\n");
+                Program::Js {
+                    code_cache: cached.then(|| code_cache(&source, true)),
+                    source,
+                    module: true,
+                }
+            }
+        };
         Ok(V8Benchmark {
-            module: compile(&wabt::wat2wasm(wat)?),
+            program,
+            options,
+            stats: options.stats.then(Default::default),
+            traps: options.traps.then(Default::default),
+            payload: Payload::for_arguments(&options.arguments),
             _phantom: PhantomData,
         })
     }
+
+    /// Take the statistics recorded so far, if [`V8Options::stats`] is set.
+    pub fn take_stats(&self) -> Vec<V8Stats> {
+        self.stats.as_ref().map(Recorder::take).unwrap_or_default()
+    }
+
+    /// The number of traps of each kind recovered from, if [`V8Options::traps`] is set.
+    pub fn trap_counts(&self) -> Vec<(Trap, usize)> {
+        self.traps
+            .as_ref()
+            .map(TrapCounter::counts)
+            .unwrap_or_default()
+    }
+
+    fn begin(&self) -> Worker {
+        Worker {
+            stats: self.stats.as_ref().map(Recorder::begin),
+            watchdog: self.options.time_limit.map(|_| Watchdog::new()),
+            terminated: None,
+        }
+    }
+
+    fn finish(&self, isolate: &mut v8::Isolate, worker: Worker) {
+        if let Some(mut stats) = worker.stats {
+            stats.sample(isolate);
+            stats.detach(isolate);
+            self.stats.as_ref().unwrap().finish(stats);
+        }
+    }
+
+    /// Prepare an isolate which will run the program repeatedly.
+    fn prepare(&self, isolate: &mut v8::Isolate) -> Prepared {
+        let scope = &mut v8::HandleScope::new(isolate);
+        let names = (!self.options.naive_calls).then(|| Names::new(scope));
+        let script = match &self.program {
+            Program::Js {
+                source,
+                module: false,
+                ..
+            } if self.options.js_compile == JsCompile::Cached => {
+                let context = v8::Context::new(scope, Default::default());
+                let scope = &mut v8::ContextScope::new(scope, context);
+                let script = compile_script(scope, source, None);
+                Some(Global::new(scope, script))
+            }
+            _ => None,
+        };
+        Prepared { script, names }
+    }
+
+    /// Load the program into a new context, as far as can be done ahead of instantiation.
+    fn load<'s>(
+        &self,
+        global: Local<'s, Object>,
+        scope: &mut v8::HandleScope<'s>,
+        prepared: &Prepared,
+    ) -> Loaded<'s> {
+        let module = match &self.program {
+            Program::Wasm(module) => {
+                Some(v8::WasmModuleObject::from_compiled_module(scope, module).unwrap())
+            }
+            Program::Js { .. } => None,
+        };
+        let call = (!self.options.naive_calls)
+            .then(|| PreparedCall::new(global, scope, prepared.names.as_ref(), module.is_some()));
+        let mut imports = None;
+        if self.options.host {
+            let host = host_object(scope);
+            let name = v8::String::new(scope, "host").unwrap();
+            if module.is_some() {
+                let object = Object::new(scope);
+                object.set(scope, name.into(), host.into());
+                imports = Some(object);
+            } else {
+                global.set(scope, name.into(), host.into());
+            }
+        }
+        Loaded {
+            module,
+            call,
+            imports,
+        }
+    }
+
+    /// Instantiate the program in the current context and return its exports.
+    fn entry<'s>(
+        &self,
+        global: Local<'s, Object>,
+        scope: &mut v8::HandleScope<'s>,
+        loaded: &Loaded<'s>,
+        prepared: &Prepared,
+    ) -> Exports<'s> {
+        let call = loaded.call.as_ref();
+        let payload = self.payload.is_some();
+        match &self.program {
+            Program::Wasm(_) => {
+                let module = loaded.module.unwrap();
+                instantiate(global, scope, module, loaded.imports, call, payload)
+            }
+            Program::Js {
+                source,
+                module: false,
+                code_cache,
+            } => {
+                let script = match &prepared.script {
+                    Some(script) => Local::new(scope, script),
+                    None => compile_script(scope, source, code_cache.as_deref()),
+                };
+                script.bind_to_current_context(scope).run(scope).unwrap();
+                Exports {
+                    add: get_add(global, scope, call),
+                    memory: None,
+                }
+            }
+            Program::Js {
+                source,
+                module: true,
+                code_cache,
+            } => {
+                let module = compile_module(scope, source, code_cache.as_deref());
+                module.instantiate_module(scope, resolve).unwrap();
+                module.evaluate(scope).unwrap();
+                let namespace = module.get_module_namespace().to_object(scope).unwrap();
+                Exports {
+                    add: get_add(namespace, scope, call),
+                    memory: None,
+                }
+            }
+        }
+    }
+
+    /// Call `add` with the arguments or payload it takes.
+    fn call_workload(
+        &self,
+        global: Local<Object>,
+        scope: &mut v8::HandleScope,
+        exports: Exports,
+    ) -> Result<Outcome> {
+        match &self.payload {
+            Some(payload) => transfer(global, scope, exports, payload),
+            None => Ok(call(global, scope, exports.add, self.options.arguments)),
+        }
+    }
+
+    /// Call `add`, terminating it if it exceeds the time limit.
+    fn invoke(
+        &self,
+        worker: &mut Worker,
+        global: Local<Object>,
+        scope: &mut v8::HandleScope,
+        exports: Exports,
+    ) -> Result<()> {
+        let Some(watchdog) = &worker.watchdog else {
+            return self.completed(self.call_workload(global, scope, exports)?);
+        };
+        watchdog.arm(scope.thread_safe_handle(), self.options.time_limit.unwrap());
+        let outcome = self.call_workload(global, scope, exports);
+        let returned = Instant::now();
+        let Some(fired) = watchdog.disarm() else {
+            return self.completed(outcome?);
+        };
+        // This also clears a termination which raced with the call returning normally.
+        scope.cancel_terminate_execution();
+        match outcome? {
+            Outcome::Terminated => {
+                if let Some(stats) = &mut worker.stats {
+                    stats.terminated(returned.saturating_duration_since(fired));
+                }
+                worker.terminated = Some(returned);
+                Ok(())
+            }
+            outcome => self.completed(outcome),
+        }
+    }
+
+    /// Account for a call which wasn't terminated, which should have thrown exactly if
+    /// [`V8Options::traps`] is set.
+    fn completed(&self, outcome: Outcome) -> Result<()> {
+        match (outcome, &self.traps) {
+            (Outcome::Returned(result), None) => self.options.arguments.check(result),
+            (Outcome::Threw(trap), Some(traps)) => {
+                traps.record(trap);
+                Ok(())
+            }
+            (Outcome::Returned(result), Some(_)) => {
+                anyhow::bail!("expected a trap, but the call returned {result}")
+            }
+            (Outcome::Threw(trap), None) => anyhow::bail!("call threw ({trap:?})"),
+            (Outcome::Terminated, _) => anyhow::bail!("call failed without being terminated"),
+        }
+    }
+
+    /// Check that a call which doesn't throw still succeeds after the traps.
+    fn check_recovered(
+        &self,
+        global: Local<Object>,
+        scope: &mut v8::HandleScope,
+        exports: Exports,
+    ) -> Result<()> {
+        let arguments = Arguments {
+            lhs: 0,
+            rhs: 2,
+            ..Default::default()
+        };
+        match call(global, scope, exports.add, arguments) {
+            Outcome::Returned(2) => Ok(()),
+            outcome => anyhow::bail!("runtime didn't recover from traps: {outcome:?}"),
+        }
+    }
+
+    /// Instantiate the program in a new context and pass its exports to `f`.
+    fn in_new_context<R>(
+        &self,
+        scope: &mut v8::HandleScope<()>,
+        prepared: &Prepared,
+        f: impl FnOnce(Local<Object>, &mut v8::HandleScope, Exports) -> R,
+    ) -> R {
+        let context = v8::Context::new(scope, Default::default());
+        let global = context.global(scope);
+        let scope = &mut v8::ContextScope::new(scope, context);
+        let loaded = self.load(global, scope, prepared);
+        let exports = self.entry(global, scope, &loaded, prepared);
+        f(global, scope, exports)
+    }
+
+    fn once_in_new_context(
+        &self,
+        scope: &mut v8::HandleScope<()>,
+        prepared: &Prepared,
+        worker: &mut Worker,
+    ) -> Result<()> {
+        self.in_new_context(scope, prepared, |global, scope, exports| {
+            self.invoke(worker, global, scope, exports)
+        })
+    }
+
+    fn check_recovered_in_new_context(
+        &self,
+        scope: &mut v8::HandleScope<()>,
+        prepared: &Prepared,
+    ) -> Result<()> {
+        self.in_new_context(scope, prepared, |global, scope, exports| {
+            self.check_recovered(global, scope, exports)
+        })
+    }
+}
+
+/// Per-thread state used by every mode.
+pub struct Worker {
+    stats: Option<ThreadStats>,
+    watchdog: Option<Watchdog>,
+    /// When the last call returned, if it was terminated.
+    terminated: Option<Instant>,
+}
+
+impl Worker {
+    fn attach(&self, isolate: &mut v8::Isolate) {
+        if let Some(stats) = &self.stats {
+            stats.attach(isolate);
+        }
+    }
+
+    /// Force a full GC on the worker's isolate.
+    fn collect(&mut self, isolate: &mut v8::Isolate) {
+        match &mut self.stats {
+            Some(stats) => stats.collect(isolate),
+            None => isolate.low_memory_notification(),
+        }
+    }
+
+    /// Record how long it took to get ready for the next call after a terminated one.
+    fn recovered(&mut self) {
+        if let (Some(returned), Some(stats)) = (self.terminated.take(), &mut self.stats) {
+            stats.recovered(returned.elapsed());
+        }
+    }
 }
 
 impl SimpleRuntime for V8Benchmark<NewIsolate> {
-    type State = ();
+    type State = Worker;
 
-    fn setup(&self) -> Self::State {
-        ()
+    fn setup(&self) -> Result<Self::State> {
+        Ok(self.begin())
     }
 
-    fn iterate(&self, _state: &mut Self::State) {
-        let isolate = &mut v8::Isolate::new(Default::default());
-        let mut handle_scope = v8::HandleScope::new(isolate);
-        let context = v8::Context::new(&mut handle_scope, Default::default());
-        let global = context.global(&mut handle_scope);
-        let mut context_scope = v8::ContextScope::new(&mut handle_scope, context);
-        let module =
-            v8::WasmModuleObject::from_compiled_module(&mut context_scope, &self.module).unwrap();
-        body(global, &mut context_scope, module);
+    fn iterate(&self, worker: &mut Self::State) -> Result<()> {
+        let mut isolate = v8::Isolate::new(Default::default());
+        worker.attach(&mut isolate);
+        let scope = &mut v8::HandleScope::new(&mut isolate);
+        let result = self.once_in_new_context(scope, &Prepared::default(), worker);
+        // The isolate is gone by the next call, so only the most recent one is recorded.
+        if let Some(stats) = &mut worker.stats {
+            stats.sample(&mut isolate);
+        }
+        core::mem::drop(isolate);
+        worker.recovered();
+        result
+    }
+
+    fn teardown(&self, worker: Self::State) -> Result<()> {
+        if self.traps.is_some() {
+            let mut isolate = v8::Isolate::new(Default::default());
+            let scope = &mut v8::HandleScope::new(&mut isolate);
+            self.check_recovered_in_new_context(scope, &Prepared::default())?;
+        }
+        // There is no long-lived isolate to collect before timing.
+        if let (Some(recorder), Some(stats)) = (&self.stats, worker.stats) {
+            recorder.finish(stats);
+        }
+        Ok(())
     }
 }
 
+pub struct IsolateState {
+    // Declared before the isolate so that it is dropped while the isolate is still alive.
+    prepared: Prepared,
+    isolate: v8::OwnedIsolate,
+    worker: Worker,
+}
+
 impl SimpleRuntime for V8Benchmark<SameIsolateNewContext> {
-    type State = v8::OwnedIsolate;
+    type State = IsolateState;
 
-    fn setup(&self) -> Self::State {
-        v8::Isolate::new(Default::default())
+    fn setup(&self) -> Result<Self::State> {
+        let mut isolate = v8::Isolate::new(Default::default());
+        let worker = self.begin();
+        worker.attach(&mut isolate);
+        Ok(IsolateState {
+            prepared: self.prepare(&mut isolate),
+            isolate,
+            worker,
+        })
     }
 
-    fn iterate(&self, state: &mut Self::State) {
-        let isolate = state;
-        let mut handle_scope = v8::HandleScope::new(isolate);
-        let context = v8::Context::new(&mut handle_scope, Default::default());
-        let global = context.global(&mut handle_scope);
-        let mut context_scope = v8::ContextScope::new(&mut handle_scope, context);
-        let module =
-            v8::WasmModuleObject::from_compiled_module(&mut context_scope, &self.module).unwrap();
-        body(global, &mut context_scope, module);
+    fn iterate(&self, state: &mut Self::State) -> Result<()> {
+        let handle_scope = &mut v8::HandleScope::new(&mut state.isolate);
+        self.once_in_new_context(handle_scope, &state.prepared, &mut state.worker)?;
+        state.worker.recovered();
+        Ok(())
+    }
+
+    fn ready(&self, state: &mut Self::State) {
+        if self.options.gc_between_levels {
+            state.worker.collect(&mut state.isolate);
+        }
+    }
+
+    fn teardown(&self, mut state: Self::State) -> Result<()> {
+        if self.traps.is_some() {
+            let handle_scope = &mut v8::HandleScope::new(&mut state.isolate);
+            self.check_recovered_in_new_context(handle_scope, &state.prepared)?;
+        }
+        self.finish(&mut state.isolate, state.worker);
+        Ok(())
     }
 }
 
@@ -97,59 +767,177 @@ impl SingleThreadedRuntime for V8Benchmark<SameIsolateSameContext> {
         duration: Duration,
         notready: &AtomicUsize,
         notdone: &AtomicUsize,
-    ) -> usize {
+    ) -> Result<usize> {
+        let mut ready = Arrival::new(notready);
+        let mut done = Arrival::new(notdone);
+        // Begun before the isolate is created, so that its GC counters outlive the isolate even
+        // if a call fails.
+        let mut worker = self.begin();
         let isolate = &mut v8::Isolate::new(Default::default());
+        worker.attach(isolate);
+        let prepared = self.prepare(isolate);
         let mut handle_scope = v8::HandleScope::new(isolate);
         let context = v8::Context::new(&mut handle_scope, Default::default());
+        let global = context.global(&mut handle_scope);
         let mut context_scope = v8::ContextScope::new(&mut handle_scope, context);
-        let module =
-            v8::WasmModuleObject::from_compiled_module(&mut context_scope, &self.module).unwrap();
+        let loaded = self.load(global, &mut context_scope, &prepared);
         core::mem::drop(context_scope);
 
-        let mut once = || {
-            let mut handle_scope = v8::HandleScope::new(&mut handle_scope);
-            let global = context.global(&mut handle_scope);
-            let mut context_scope = v8::ContextScope::new(&mut handle_scope, context);
-            body(global, &mut context_scope, module);
+        // Set to force a full GC before the next call.
+        let collect = Cell::new(false);
+        let mut once = || -> Result<()> {
+            if collect.take() {
+                worker.collect(&mut handle_scope);
+            }
+            {
+                let mut handle_scope = v8::HandleScope::new(&mut handle_scope);
+                let global = context.global(&mut handle_scope);
+                let mut context_scope = v8::ContextScope::new(&mut handle_scope, context);
+                let exports = self.entry(global, &mut context_scope, &loaded, &prepared);
+                self.invoke(&mut worker, global, &mut context_scope, exports)?;
+            }
+            worker.recovered();
+            Ok(())
         };
 
         let warmup_start = Instant::now();
         while warmup_start.elapsed() < warmup {
-            once();
+            once()?;
         }
-        notready.fetch_sub(1, Ordering::Release);
+        if self.options.gc_between_levels {
+            collect.set(true);
+            once()?;
+        }
+        ready.arrive();
         while notready.load(Ordering::Acquire) != 0 {
-            once();
+            once()?;
         }
         let start = Instant::now();
         let mut iters = 0;
         loop {
-            once();
+            once()?;
             if start.elapsed() < duration {
                 iters += 1;
             } else {
                 break;
             }
         }
-        notdone.fetch_sub(1, Ordering::Release);
+        done.arrive();
         while notready.load(Ordering::Acquire) != 0 {
-            once();
+            once()?;
         }
-        iters
+        if self.traps.is_some() {
+            let mut handle_scope = v8::HandleScope::new(&mut handle_scope);
+            let global = context.global(&mut handle_scope);
+            let mut context_scope = v8::ContextScope::new(&mut handle_scope, context);
+            let exports = self.entry(global, &mut context_scope, &loaded, &prepared);
+            self.check_recovered(global, &mut context_scope, exports)?;
+        }
+        core::mem::drop(handle_scope);
+        self.finish(isolate, worker);
+        Ok(iters)
     }
 }
 
-fn body(
-    global: Local<Object>,
+/// `host.add`, the host function workloads call, which adds its arguments like the one wasm2c
+/// modules import.
+fn host_add(
     scope: &mut v8::HandleScope,
+    args: v8::FunctionCallbackArguments,
+    mut rv: v8::ReturnValue,
+) {
+    let lhs = args.get(0).uint32_value(scope).unwrap_or_default();
+    let rhs = args.get(1).uint32_value(scope).unwrap_or_default();
+    rv.set_uint32(lhs.wrapping_add(rhs));
+}
+
+/// A new `host` object holding the host functions.
+fn host_object<'s>(scope: &mut v8::HandleScope<'s>) -> Local<'s, Object> {
+    let host = Object::new(scope);
+    let add = Function::new(scope, host_add).unwrap();
+    let name = v8::String::new(scope, "add").unwrap();
+    host.set(scope, name.into(), add.into());
+    host
+}
+
+/// Look up the `add` function on an object.
+fn get_add<'s>(
+    object: Local<'s, Object>,
+    scope: &mut v8::HandleScope<'s>,
+    call: Option<&PreparedCall<'s>>,
+) -> Local<'s, Function> {
+    let add = match call {
+        Some(call) => call.add,
+        None => v8::String::new(scope, "add").unwrap(),
+    };
+    object
+        .get(scope, add.into())
+        .unwrap()
+        .to_object(scope)
+        .unwrap()
+        .cast::<Function>()
+}
+
+/// Look up the exports of a wasm instance which a call uses.
+fn wasm_exports<'s>(
+    exports: Local<'s, Object>,
+    scope: &mut v8::HandleScope<'s>,
+    call: Option<&PreparedCall<'s>>,
+    payload: bool,
+) -> Exports<'s> {
+    let add = get_add(exports, scope, call);
+    let memory = payload.then(|| {
+        let [reserve, memory, buffer] = match call {
+            Some(call) => [call.reserve, call.memory, call.buffer],
+            None => {
+                ["reserve", "memory", "buffer"].map(|name| v8::String::new(scope, name).unwrap())
+            }
+        };
+        let reserve = exports
+            .get(scope, reserve.into())
+            .unwrap()
+            .to_object(scope)
+            .unwrap()
+            .cast::<Function>();
+        let memory = exports
+            .get(scope, memory.into())
+            .unwrap()
+            .to_object(scope)
+            .unwrap();
+        WasmMemory {
+            reserve,
+            memory,
+            buffer,
+        }
+    });
+    Exports { add, memory }
+}
+
+fn instantiate<'s>(
+    global: Local<'s, Object>,
+    scope: &mut v8::HandleScope<'s>,
     module: Local<WasmModuleObject>,
-) -> u32 {
+    imports: Option<Local<Object>>,
+    call: Option<&PreparedCall<'s>>,
+    payload: bool,
+) -> Exports<'s> {
+    let args = match imports {
+        Some(imports) => vec![module.into(), imports.into()],
+        None => vec![module.into()],
+    };
+    if let Some(call) = call {
+        let instance = call.instance.unwrap().new_instance(scope, &args).unwrap();
+        let exports = instance
+            .get(scope, call.exports.into())
+            .unwrap()
+            .to_object(scope)
+            .unwrap();
+        return wasm_exports(exports, scope, Some(call), payload);
+    }
+
     let webassembly = v8::String::new(scope, "WebAssembly").unwrap().into();
     let instance = v8::String::new(scope, "Instance").unwrap().into();
     let exports = v8::String::new(scope, "exports").unwrap().into();
-    let add = v8::String::new(scope, "add").unwrap().into();
-    let x = v8::Number::new(scope, 1.);
-    let y = v8::Number::new(scope, 2.);
     let webassembly = global
         .get(scope, webassembly)
         .unwrap()
@@ -162,7 +950,7 @@ fn body(
         .unwrap();
     let instance = instance.cast::<v8::Function>();
     let instance = instance
-        .new_instance(scope, &[module.into()])
+        .new_instance(scope, &args)
         .unwrap()
         .to_object(scope)
         .unwrap();
@@ -171,15 +959,132 @@ fn body(
         .unwrap()
         .to_object(scope)
         .unwrap();
-    let add = exports
-        .get(scope, add)
-        .unwrap()
-        .to_object(scope)
-        .unwrap()
-        .cast::<v8::Function>();
-    let result = add
-        .call(scope, global.into(), &[x.into(), y.into()])
-        .unwrap();
-    let result = result.to_uint32(scope).unwrap();
-    result.value()
+    wasm_exports(exports, scope, None, payload)
+}
+
+/// How a call to `add` ended.
+#[derive(Debug)]
+enum Outcome {
+    Returned(u32),
+    Threw(Trap),
+    Terminated,
+}
+
+/// Classify an exception by its message, which is how V8 distinguishes wasm traps (as
+/// `RuntimeError`s) and how JavaScript workloads report the equivalent failures.
+fn classify(message: &str) -> Trap {
+    let message = message.to_lowercase();
+    if message.contains("out of bounds") || message.contains("outside the bounds") {
+        Trap::OutOfBounds
+    } else if message.contains("maximum call stack size exceeded") {
+        Trap::StackOverflow
+    } else if message.contains("divide by zero") || message.contains("division by zero") {
+        Trap::DivideByZero
+    } else if message.contains("unreachable") {
+        Trap::Unreachable
+    } else {
+        Trap::Other
+    }
+}
+
+/// Call `add`, catching any exception it throws.
+fn call(
+    global: Local<Object>,
+    scope: &mut v8::HandleScope,
+    add: Local<Function>,
+    arguments: Arguments,
+) -> Outcome {
+    let scope = &mut v8::TryCatch::new(scope);
+    let x = v8::Number::new(scope, arguments.lhs.into());
+    let y = v8::Number::new(scope, arguments.rhs.into());
+    match add.call(scope, global.into(), &[x.into(), y.into()]) {
+        Some(result) => Outcome::Returned(result.to_uint32(scope).unwrap().value()),
+        None => caught(scope),
+    }
+}
+
+/// How a call which didn't return ended.
+fn caught(scope: &mut v8::TryCatch<v8::HandleScope>) -> Outcome {
+    if scope.has_terminated() {
+        return Outcome::Terminated;
+    }
+    let exception = scope.exception().unwrap();
+    Outcome::Threw(classify(&exception.to_rust_string_lossy(scope)))
+}
+
+/// Pass a [`Payload`] to `add` and check the output it passes back, catching any exception it
+/// throws. A call which returns is reported as returning the output's length.
+fn transfer(
+    global: Local<Object>,
+    scope: &mut v8::HandleScope,
+    exports: Exports,
+    payload: &Payload,
+) -> Result<Outcome> {
+    let scope = &mut v8::TryCatch::new(scope);
+    let output = match exports.memory {
+        Some(memory) => transfer_memory(global, scope, exports.add, memory, &payload.input),
+        None => transfer_array_buffer(global, scope, exports.add, &payload.input),
+    };
+    match output {
+        Some(output) => {
+            payload.check(&output)?;
+            Ok(Outcome::Returned(output.len() as u32))
+        }
+        None => Ok(caught(scope)),
+    }
+}
+
+/// Write `input` into a wasm instance's memory and read the output back out of it.
+fn transfer_memory(
+    global: Local<Object>,
+    scope: &mut v8::HandleScope,
+    add: Local<Function>,
+    memory: WasmMemory,
+    input: &[u8],
+) -> Option<Vec<u8>> {
+    let len = v8::Number::new(scope, input.len() as f64);
+    let offset = memory.reserve.call(scope, global.into(), &[len.into()])?;
+    let offset = offset.to_uint32(scope)?;
+    // `reserve` may have grown the memory, which detaches its old buffer, so it's only looked up
+    // afterwards. `add` doesn't grow it.
+    let buffer = memory.memory.get(scope, memory.buffer.into())?;
+    let store = buffer.cast::<v8::ArrayBuffer>().get_backing_store();
+    let start = offset.value() as usize;
+    unsafe { contents(&store) }
+    [start..start + input.len()].copy_from_slice(input);
+    let output = add.call(scope, global.into(), &[len.into(), offset.into()])?;
+    let start = output.to_uint32(scope)?.value() as usize;
+    Some(unsafe { contents(&store) }[start..start + input.len()].to_vec())
+}
+
+/// Pass `input` to a JavaScript workload in a new `ArrayBuffer`, and copy the `ArrayBuffer` it
+/// returns.
+fn transfer_array_buffer(
+    global: Local<Object>,
+    scope: &mut v8::HandleScope,
+    add: Local<Function>,
+    input: &[u8],
+) -> Option<Vec<u8>> {
+    let buffer = v8::ArrayBuffer::new(scope, input.len());
+    unsafe { contents(&buffer.get_backing_store()) }.copy_from_slice(input);
+    let output = add.call(scope, global.into(), &[buffer.into()])?;
+    let output: Local<v8::ArrayBuffer> = output
+        .try_into()
+        .expect("payload workloads return an ArrayBuffer");
+    Some(unsafe { contents(&output.get_backing_store()) }.to_vec())
+}
+
+/// The contents of an `ArrayBuffer`.
+///
+/// # Safety
+///
+/// No JavaScript or wasm may run while they're borrowed.
+#[allow(clippy::mut_from_ref)]
+unsafe fn contents(store: &v8::SharedRef<v8::BackingStore>) -> &mut [u8] {
+    match store.data() {
+        Some(data) => unsafe {
+            std::slice::from_raw_parts_mut(data.as_ptr().cast(), store.byte_length())
+        },
+        None => &mut [],
+    }
 }
@@ -0,0 +1,330 @@
+use std::{
+    cell::Cell,
+    ffi::c_void,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Heap and GC statistics for a single worker thread over one parallelism level.
+#[derive(Debug, Copy, Clone, Default, Serialize)]
+pub struct V8Stats {
+    pub parallel: usize,
+    pub total_heap_size: usize,
+    pub used_heap_size: usize,
+    pub total_physical_size: usize,
+    pub malloced_memory: usize,
+    pub external_memory: usize,
+    pub native_contexts: usize,
+    pub detached_contexts: usize,
+    pub scavenge_count: usize,
+    pub scavenge_ns: u128,
+    pub minor_mark_sweep_count: usize,
+    pub minor_mark_sweep_ns: u128,
+    pub mark_sweep_compact_count: usize,
+    pub mark_sweep_compact_ns: u128,
+    pub incremental_marking_count: usize,
+    pub incremental_marking_ns: u128,
+    pub weak_callbacks_count: usize,
+    pub weak_callbacks_ns: u128,
+    /// Number of V8 platform worker threads.
+    pub background_threads: usize,
+    /// CPU time used by V8's platform worker threads over the whole level (shared by all threads).
+    pub background_cpu_ns: u128,
+    /// Wall-clock time of the whole level, from the first worker starting to the last finishing.
+    pub level_ns: u128,
+    /// Number of calls terminated for exceeding the time limit.
+    pub terminations: usize,
+    /// Total time from requesting termination to the terminated call returning.
+    pub termination_latency_ns: u128,
+    /// Total time from a terminated call returning to being ready for the next call.
+    pub recovery_ns: u128,
+    /// Number of full GCs forced before the level's timed calls, which aren't counted above.
+    pub forced_gc_count: usize,
+    /// Total time spent in GCs forced before the level's timed calls.
+    pub forced_gc_ns: u128,
+}
+
+const GC_TYPES: usize = 5;
+
+fn gc_index(ty: v8::GCType) -> Option<usize> {
+    if ty == v8::GCType::kGCTypeScavenge {
+        Some(0)
+    } else if ty == v8::GCType::kGCTypeMinorMarkSweep {
+        Some(1)
+    } else if ty == v8::GCType::kGCTypeMarkSweepCompact {
+        Some(2)
+    } else if ty == v8::GCType::kGCTypeIncrementalMarking {
+        Some(3)
+    } else if ty == v8::GCType::kGCTypeProcessWeakCallbacks {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+#[derive(Default)]
+struct GcCounters {
+    started: Cell<Option<Instant>>,
+    count: [Cell<usize>; GC_TYPES],
+    pause: [Cell<Duration>; GC_TYPES],
+}
+
+extern "C" fn gc_prologue(
+    _isolate: *mut v8::Isolate,
+    _ty: v8::GCType,
+    _flags: v8::GCCallbackFlags,
+    data: *mut c_void,
+) {
+    let counters = unsafe { &*(data as *const GcCounters) };
+    counters.started.set(Some(Instant::now()));
+}
+
+extern "C" fn gc_epilogue(
+    _isolate: *mut v8::Isolate,
+    ty: v8::GCType,
+    _flags: v8::GCCallbackFlags,
+    data: *mut c_void,
+) {
+    let counters = unsafe { &*(data as *const GcCounters) };
+    let (Some(started), Some(i)) = (counters.started.take(), gc_index(ty)) else {
+        return;
+    };
+    counters.count[i].set(counters.count[i].get() + 1);
+    counters.pause[i].set(counters.pause[i].get() + started.elapsed());
+}
+
+/// Statistics being gathered by one worker thread.
+///
+/// The GC counters are boxed so that their address stays stable while they are attached to an
+/// isolate; they must be detached from (or outlived by) every isolate they are attached to.
+pub struct ThreadStats {
+    gc: Box<GcCounters>,
+    stats: V8Stats,
+}
+
+impl ThreadStats {
+    /// Register the GC callbacks on an isolate.
+    pub fn attach(&self, isolate: &mut v8::Isolate) {
+        let data = &*self.gc as *const GcCounters as *mut c_void;
+        isolate.add_gc_prologue_callback(gc_prologue, data, v8::GCType::kGCTypeAll);
+        isolate.add_gc_epilogue_callback(gc_epilogue, data, v8::GCType::kGCTypeAll);
+    }
+
+    /// Unregister the GC callbacks from an isolate.
+    pub fn detach(&self, isolate: &mut v8::Isolate) {
+        let data = &*self.gc as *const GcCounters as *mut c_void;
+        isolate.remove_gc_prologue_callback(gc_prologue, data);
+        isolate.remove_gc_epilogue_callback(gc_epilogue, data);
+    }
+
+    /// Record the current heap statistics of an isolate, replacing any previous sample.
+    pub fn sample(&mut self, isolate: &mut v8::Isolate) {
+        let heap = isolate.get_heap_statistics();
+        self.stats.total_heap_size = heap.total_heap_size();
+        self.stats.used_heap_size = heap.used_heap_size();
+        self.stats.total_physical_size = heap.total_physical_size();
+        self.stats.malloced_memory = heap.malloced_memory();
+        self.stats.external_memory = heap.external_memory();
+        self.stats.native_contexts = heap.number_of_native_contexts();
+        self.stats.detached_contexts = heap.number_of_detached_contexts();
+    }
+
+    /// Force a full GC on an isolate, recording it apart from the GCs which calls cause.
+    pub fn collect(&mut self, isolate: &mut v8::Isolate) {
+        let gc = &*self.gc;
+        let count = gc.count.each_ref().map(Cell::get);
+        let pause = gc.pause.each_ref().map(Cell::get);
+        let started = Instant::now();
+        isolate.low_memory_notification();
+        self.stats.forced_gc_count += 1;
+        self.stats.forced_gc_ns += started.elapsed().as_nanos();
+        for (cell, count) in gc.count.iter().zip(count) {
+            cell.set(count);
+        }
+        for (cell, pause) in gc.pause.iter().zip(pause) {
+            cell.set(pause);
+        }
+    }
+
+    /// Record a call which was terminated, and how long it took to return after being terminated.
+    pub fn terminated(&mut self, latency: Duration) {
+        self.stats.terminations += 1;
+        self.stats.termination_latency_ns += latency.as_nanos();
+    }
+
+    /// Record how long it took to recover from a terminated call.
+    pub fn recovered(&mut self, recovery: Duration) {
+        self.stats.recovery_ns += recovery.as_nanos();
+    }
+
+    fn finish(mut self, parallel: usize) -> V8Stats {
+        let gc = &*self.gc;
+        let count = |i: usize| gc.count[i].get();
+        let pause = |i: usize| gc.pause[i].get().as_nanos();
+        self.stats.parallel = parallel;
+        self.stats.scavenge_count = count(0);
+        self.stats.scavenge_ns = pause(0);
+        self.stats.minor_mark_sweep_count = count(1);
+        self.stats.minor_mark_sweep_ns = pause(1);
+        self.stats.mark_sweep_compact_count = count(2);
+        self.stats.mark_sweep_compact_ns = pause(2);
+        self.stats.incremental_marking_count = count(3);
+        self.stats.incremental_marking_ns = pause(3);
+        self.stats.weak_callbacks_count = count(4);
+        self.stats.weak_callbacks_ns = pause(4);
+        self.stats
+    }
+}
+
+/// Total CPU time used so far by V8's platform threads, and how many of them there are.
+///
+/// V8 names its platform threads "V8 DefaultWorker" and so on, so they can be picked out of
+/// `/proc/self/task`; `schedstat` has their on-CPU time in nanoseconds.
+fn background_cpu() -> (usize, Duration) {
+    let mut threads = 0;
+    let mut cpu = Duration::ZERO;
+    let Ok(tasks) = std::fs::read_dir("/proc/self/task") else {
+        return (threads, cpu);
+    };
+    for task in tasks.flatten() {
+        let path = task.path();
+        let Ok(comm) = std::fs::read_to_string(path.join("comm")) else {
+            continue;
+        };
+        if !comm.starts_with("V8 ") {
+            continue;
+        }
+        let Ok(schedstat) = std::fs::read_to_string(path.join("schedstat")) else {
+            continue;
+        };
+        if let Some(ns) = schedstat
+            .split_whitespace()
+            .next()
+            .and_then(|ns| ns.parse().ok())
+        {
+            threads += 1;
+            cpu += Duration::from_nanos(ns);
+        }
+    }
+    (threads, cpu)
+}
+
+#[derive(Default)]
+struct Level {
+    started: usize,
+    start: Option<(Instant, Duration)>,
+    finished: Vec<V8Stats>,
+}
+
+/// Collects [`ThreadStats`] from every worker thread.
+///
+/// Worker threads don't know the parallelism level they're running at, but every worker of a
+/// level calls [`begin`](Recorder::begin) before any of them can pass the warmup barrier and call
+/// [`finish`](Recorder::finish), so the level is the number of workers which have begun since the
+/// last level completed.
+#[derive(Default)]
+pub struct Recorder {
+    level: Mutex<Level>,
+    data: Mutex<Vec<V8Stats>>,
+}
+
+impl Recorder {
+    pub fn begin(&self) -> ThreadStats {
+        let mut level = self.level.lock().unwrap();
+        if level.started == 0 {
+            level.start = Some((Instant::now(), background_cpu().1));
+        }
+        level.started += 1;
+        ThreadStats {
+            gc: Default::default(),
+            stats: Default::default(),
+        }
+    }
+
+    pub fn finish(&self, stats: ThreadStats) {
+        let mut level = self.level.lock().unwrap();
+        let parallel = level.started;
+        level.finished.push(stats.finish(parallel));
+        if level.finished.len() < parallel {
+            return;
+        }
+        let mut finished = core::mem::take(&mut level.finished);
+        let (start, start_cpu) = level.start.take().unwrap();
+        level.started = 0;
+        core::mem::drop(level);
+
+        let (background_threads, cpu) = background_cpu();
+        let background = cpu.saturating_sub(start_cpu);
+        let elapsed = start.elapsed();
+        for datum in &mut finished {
+            datum.background_threads = background_threads;
+            datum.background_cpu_ns = background.as_nanos();
+            datum.level_ns = elapsed.as_nanos();
+        }
+
+        let n = finished.len();
+        let used = finished.iter().map(|x| x.used_heap_size).sum::<usize>() / n;
+        let physical = finished
+            .iter()
+            .map(|x| x.total_physical_size)
+            .sum::<usize>()
+            / n;
+        let gcs = finished
+            .iter()
+            .map(|x| {
+                x.scavenge_count
+                    + x.minor_mark_sweep_count
+                    + x.mark_sweep_compact_count
+                    + x.incremental_marking_count
+            })
+            .sum::<usize>()
+            / n;
+        let pause = finished
+            .iter()
+            .map(|x| {
+                x.scavenge_ns
+                    + x.minor_mark_sweep_ns
+                    + x.mark_sweep_compact_ns
+                    + x.incremental_marking_ns
+                    + x.weak_callbacks_ns
+            })
+            .sum::<u128>()
+            / n as u128;
+        let pause = Duration::from_nanos(pause as u64);
+        println!(
+            "{parallel:4} threads: {used:>10} bytes used, {physical:>10} bytes physical, {gcs:6} GCs ({pause:?} paused) per thread",
+        );
+        let forced = finished.iter().map(|x| x.forced_gc_count).sum::<usize>();
+        if forced > 0 {
+            let pause = finished.iter().map(|x| x.forced_gc_ns).sum::<u128>() / forced as u128;
+            let pause = Duration::from_nanos(pause as u64);
+            println!("{parallel:4} threads: {forced} GCs forced before timing, {pause:?} each");
+        }
+        let load = background.as_secs_f64() / elapsed.as_secs_f64();
+        println!(
+            "{parallel:4} threads: {background:?} of CPU time ({load:.2} cores) on {background_threads} V8 background threads",
+        );
+        let terminations = finished.iter().map(|x| x.terminations).sum::<usize>();
+        if terminations > 0 {
+            let latency = finished
+                .iter()
+                .map(|x| x.termination_latency_ns)
+                .sum::<u128>()
+                / terminations as u128;
+            let recovery =
+                finished.iter().map(|x| x.recovery_ns).sum::<u128>() / terminations as u128;
+            let latency = Duration::from_nanos(latency as u64);
+            let recovery = Duration::from_nanos(recovery as u64);
+            println!(
+                "{parallel:4} threads: {terminations} terminations, {latency:?} to terminate, {recovery:?} to recover",
+            );
+        }
+        self.data.lock().unwrap().extend(finished);
+    }
+
+    pub fn take(&self) -> Vec<V8Stats> {
+        core::mem::take(&mut self.data.lock().unwrap())
+    }
+}
@@ -0,0 +1,87 @@
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+#[derive(Default)]
+struct State {
+    armed: Option<(Instant, v8::IsolateHandle)>,
+    fired: Option<Instant>,
+    exit: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+/// Terminates execution in an isolate once a deadline passes.
+///
+/// Each worker thread has its own watchdog thread, so that a runaway call on one worker doesn't
+/// delay the termination of another.
+pub struct Watchdog {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    pub fn new() -> Self {
+        let shared: Arc<Shared> = Default::default();
+        let inner = shared.clone();
+        let thread = std::thread::spawn(move || {
+            let mut state = inner.state.lock().unwrap();
+            while !state.exit {
+                let deadline = state.armed.as_ref().map(|(deadline, _)| *deadline);
+                let Some(deadline) = deadline else {
+                    state = inner.condvar.wait(state).unwrap();
+                    continue;
+                };
+                let now = Instant::now();
+                if now < deadline {
+                    state = inner.condvar.wait_timeout(state, deadline - now).unwrap().0;
+                    continue;
+                }
+                let (_, isolate) = state.armed.take().unwrap();
+                isolate.terminate_execution();
+                state.fired = Some(now);
+            }
+        });
+        Watchdog {
+            shared,
+            thread: Some(thread),
+        }
+    }
+
+    /// Terminate execution in `isolate` if [`disarm`](Watchdog::disarm) isn't called within
+    /// `limit`.
+    pub fn arm(&self, isolate: v8::IsolateHandle, limit: Duration) {
+        let mut state = self.shared.state.lock().unwrap();
+        // A watchdog which is already waiting for an earlier deadline will see this one when it
+        // wakes up, so it only needs to be notified if it's idle.
+        let idle = state.armed.is_none();
+        state.armed = Some((Instant::now() + limit, isolate));
+        state.fired = None;
+        if idle {
+            self.shared.condvar.notify_one();
+        }
+    }
+
+    /// Cancel the deadline, returning when execution was terminated if it already passed.
+    pub fn disarm(&self) -> Option<Instant> {
+        let mut state = self.shared.state.lock().unwrap();
+        state.armed = None;
+        state.fired.take()
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().exit = true;
+        self.shared.condvar.notify_one();
+        if let Some(thread) = self.thread.take() {
+            thread.join().unwrap();
+        }
+    }
+}
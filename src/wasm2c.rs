@@ -1,92 +1,243 @@
-use std::{mem::MaybeUninit, process::Command, sync::Arc};
+use std::{
+    ffi::c_void,
+    mem::MaybeUninit,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+};
 
-use crate::SimpleRuntime;
+use crate::{Arguments, Payload, SimpleRuntime, Trap, TrapCounter, wasm_binary};
 use anyhow::Result;
 use include_directory::{Dir, include_directory};
 use ouroboros::self_referencing;
 
+mod aot;
+mod cache;
+mod config;
+mod error;
+mod imports;
+
+pub use aot::{AotBenchmark, aot_modules};
+pub use cache::Cache;
+use cache::{Key, SharedObject};
+pub use config::{
+    CompilerConfig, CompilerOverride, Instantiation, PoolReset, RuntimeConfig, Wasm2CConfig,
+};
+pub use error::{WASM2C_VERSION, Wasm2CError};
+use error::{check_version, run};
+
 static WASM2C_RT: Dir<'_> = include_directory!("$CARGO_MANIFEST_DIR/wasm2c");
 
+/// The wasm exports the benchmark calls, and the symbols wasm2c generates for them.
+const EXPORTS: &[(&str, &[u8])] = &[("add", b"w2c_module_add")];
+
+/// The further exports payload workloads need (see [`Payload`]).
+const PAYLOAD_EXPORTS: &[(&str, &[u8])] = &[
+    ("reserve", b"w2c_module_reserve"),
+    ("memory", b"w2c_module_memory"),
+];
+
+/// The start of `wasm_rt_memory_t`.
+#[repr(C)]
+struct Memory {
+    data: *mut u8,
+    _pages: u64,
+    _max_pages: u64,
+    size: u64,
+}
+
 pub struct Wasm2CBenchmark {
     lib: Arc<libloading::Library>,
+    /// Where `lib` was loaded from, which no other module loads while it's in use.
+    _so: SharedObject,
+    instantiation: Instantiation,
+    traps: Option<TrapCounter>,
+    arguments: Arguments,
+    payload: Option<Payload>,
 }
 
 impl Wasm2CBenchmark {
+    /// Compile `module` (in text or binary format) with wasm2c, reusing a previously compiled copy
+    /// from the [`Cache`] if the configuration allows it.
+    ///
     /// # Safety
     ///
-    /// This module must expose a function named "add" which takes two i32s and returns an i32.
-    pub unsafe fn new(wat: &[u8], hardware: bool) -> Result<Self> {
-        let wasm = wabt::wat2wasm(wat)?;
-        let temp_dir = tempfile::tempdir()?;
-        let mut wasm_file = temp_dir.path().to_path_buf();
-        wasm_file.push("module.wasm");
-        std::fs::write(&wasm_file, &wasm)?;
-        let mut c_file = temp_dir.path().to_path_buf();
-        c_file.push("module.c");
-
-        // Using wasm2c 1.0.34 from the Ubuntu repos
-        let wasm2c = Command::new("wasm2c")
-            .args([
-                "-o",
-                c_file.to_str().unwrap(),
-                "-n",
-                "module",
-                wasm_file.to_str().unwrap(),
-            ])
-            .status()?;
-        assert!(wasm2c.success());
-        WASM2C_RT.extract(&temp_dir)?;
-
-        let mut so_file = temp_dir.path().to_path_buf();
-        so_file.push("module.so");
-
-        let mut lib = temp_dir.path().to_path_buf();
-        lib.push("lib.c");
-
-        let mut wasm_rt_impl = temp_dir.path().to_path_buf();
-        wasm_rt_impl.push("wasm-rt-impl.c");
-
-        let flags = if hardware {
-            vec![
-                "-DWASM_RT_USE_MMAP=1",
-                "-DWASM_RT_MEMCHECK_GUARD_PAGES=1",
-                "-DWASM_RT_MEMCHECK_BOUNDS_CHECK=0",
-            ]
+    /// This module must expose a function named "add" which takes two i32s and returns an i32,
+    /// and for payload workloads the other exports described by [`Payload`]. It may import any of
+    /// the [`host_functions`].
+    pub unsafe fn new(module: &[u8], config: &Wasm2CConfig) -> Result<Self> {
+        config.validate()?;
+        let wasm = wasm_binary(module)?;
+        let imports = Imports::resolve(&wasm, &host_functions())?;
+        let mut flags = config.runtime.flags();
+        flags.extend(config.compiler.flags());
+        let cc = &config.compiler.cc;
+
+        let so = if config.cache {
+            let cache = Cache::new()?;
+            let key = Key::new(&wasm, imports.glue(), &WASM2C_RT, cc, &flags)?;
+            let so_file = match cache.get(&key) {
+                Some(so_file) => so_file,
+                None => {
+                    let temp_dir = tempfile::tempdir()?;
+                    let so_file = compile(&wasm, imports.glue(), cc, &flags, temp_dir.path())?;
+                    cache.insert(&key, &so_file)?
+                }
+            };
+            SharedObject::cached(so_file)?
         } else {
-            vec![
-                "-DWASM_RT_USE_MMAP=0",
-                "-DWASM_RT_MEMCHECK_GUARD_PAGES=0",
-                "-DWASM_RT_MEMCHECK_BOUNDS_CHECK=1",
-            ]
+            let temp_dir = tempfile::tempdir()?;
+            let so_file = compile(&wasm, imports.glue(), cc, &flags, temp_dir.path())?;
+            SharedObject::private(temp_dir, so_file)
         };
 
-        let cc = Command::new("cc")
-            .args([
-                "-o",
-                so_file.to_str().unwrap(),
-                "-I",
-                temp_dir.path().to_str().unwrap(),
-                c_file.to_str().unwrap(),
-                lib.to_str().unwrap(),
-                wasm_rt_impl.to_str().unwrap(),
-                "-lm",
-                "-fPIC",
-                "-shared",
-                "-O2",
-                "-fno-optimize-sibling-calls",
-                "-frounding-math",
-                "-fsignaling-nans",
-            ])
-            .args(flags)
-            .status()?;
-        assert!(cc.success());
-
         unsafe {
-            let lib = libloading::Library::new(so_file)?;
+            let lib = libloading::Library::new(so.path())?;
+            let payload_exports = if config.arguments.payload {
+                PAYLOAD_EXPORTS
+            } else {
+                &[]
+            };
+            for (name, symbol) in EXPORTS.iter().chain(payload_exports) {
+                if let Err(source) = lib.get::<*const c_void>(symbol) {
+                    return Err(Wasm2CError::MissingExport {
+                        name: name.to_string(),
+                        source,
+                    }
+                    .into());
+                }
+            }
+            imports.bind(&lib);
             let wasm_rt_init: libloading::Symbol<unsafe extern "C" fn()> =
                 lib.get(b"wasm_rt_init")?;
             wasm_rt_init();
-            Ok(Wasm2CBenchmark { lib: Arc::new(lib) })
+            Ok(Wasm2CBenchmark {
+                lib: Arc::new(lib),
+                _so: so,
+                instantiation: config.instantiation,
+                traps: config.traps.then(Default::default),
+                arguments: config.arguments,
+                payload: Payload::for_arguments(&config.arguments),
+            })
+        }
+    }
+}
+
+/// Check the installed wasm2c's version, then translate `wasm` to C and compile it along with its
+/// import `glue` with `cc` into a shared object in `dir`, returning its path.
+fn compile(wasm: &[u8], glue: &str, cc: &str, flags: &[String], dir: &Path) -> Result<PathBuf> {
+    check_version()?;
+    let wasm_file = dir.join("module.wasm");
+    std::fs::write(&wasm_file, wasm)?;
+    let c_file = dir.join("module.c");
+
+    // Using wasm2c 1.0.34 from the Ubuntu repos
+    run(Command::new("wasm2c").args([
+        "-o",
+        c_file.to_str().unwrap(),
+        "-n",
+        "module",
+        wasm_file.to_str().unwrap(),
+    ]))?;
+    WASM2C_RT.extract(dir)?;
+
+    let so_file = dir.join("module.so");
+    let lib = dir.join("lib.c");
+    let wasm_rt_impl = dir.join("wasm-rt-impl.c");
+    let imports = dir.join("imports.c");
+    std::fs::write(&imports, glue)?;
+
+    run(Command::new(cc)
+        .args([
+            "-o",
+            so_file.to_str().unwrap(),
+            "-I",
+            dir.to_str().unwrap(),
+            c_file.to_str().unwrap(),
+            lib.to_str().unwrap(),
+            wasm_rt_impl.to_str().unwrap(),
+            imports.to_str().unwrap(),
+        ])
+        .args(flags))?;
+    Ok(so_file)
+}
+
+/// Classify a `wasm_rt_trap_t`.
+///
+/// The signal handler reports a fault on any inaccessible page as out of bounds, and a thread's
+/// stack guard page is one, so stack overflows it catches are usually classified that way.
+fn classify(code: u32) -> Trap {
+    match code {
+        1 => Trap::OutOfBounds,
+        3 => Trap::DivideByZero,
+        5 => Trap::Unreachable,
+        9 => Trap::StackOverflow,
+        _ => Trap::Other,
+    }
+}
+
+impl Wasm2CBenchmark {
+    /// The number of traps of each kind recovered from, if [`Wasm2CConfig::traps`] is set.
+    pub fn trap_counts(&self) -> Vec<(Trap, usize)> {
+        self.traps
+            .as_ref()
+            .map(TrapCounter::counts)
+            .unwrap_or_default()
+    }
+
+    /// Call `add`. Traps are only caught (and counted) if the workload is expected to trap, so
+    /// that other workloads are called without the `setjmp`.
+    unsafe fn call(&self, entry: Entry, module: *mut c_void) -> Result<()> {
+        let Arguments { lhs, rhs, .. } = self.arguments;
+        if let Some(payload) = &self.payload {
+            return unsafe { transfer(entry, module, payload) };
+        }
+        unsafe {
+            let Some(traps) = &self.traps else {
+                return self.arguments.check((entry.add)(module, lhs, rhs));
+            };
+            let mut result = 0;
+            match (entry.try_add)(module, lhs, rhs, &mut result) {
+                0 => anyhow::bail!("expected a trap, but the call returned {result}"),
+                code => traps.record(classify(code)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a call which doesn't trap still succeeds after the traps.
+    unsafe fn check_recovered(&self, entry: Entry, module: *mut c_void) -> Result<()> {
+        if self.traps.is_none() {
+            return Ok(());
+        }
+        let mut result = 0;
+        unsafe {
+            if self.instantiation != Instantiation::Reuse {
+                (entry.instantiate)(module);
+            }
+            let code = (entry.try_add)(module, 0, 2, &mut result);
+            if self.instantiation != Instantiation::Reuse {
+                (entry.free)(module);
+            }
+            anyhow::ensure!(
+                (code, result) == (0, 2),
+                "runtime didn't recover from traps"
+            );
+        }
+        Ok(())
+    }
+
+    /// Set how freed memories are recycled on this thread (see `wasm_rt_set_memory_pool`).
+    fn set_memory_pool(&self, reset: Option<PoolReset>) {
+        let mode = match reset {
+            None => 0,
+            Some(PoolReset::Madvise) => 1,
+            Some(PoolReset::Zero) => 2,
+        };
+        unsafe {
+            let set_memory_pool: libloading::Symbol<unsafe extern "C" fn(u32) -> bool> =
+                self.lib.get(b"wasm_rt_set_memory_pool").unwrap();
+            assert!(set_memory_pool(mode), "memory pooling needs mmap");
         }
     }
 }
@@ -101,54 +252,151 @@ impl Drop for Wasm2CBenchmark {
     }
 }
 
+/// Copy a payload into the module's memory, call `add` on it, and copy the output back out.
+unsafe fn transfer(entry: Entry, module: *mut c_void, payload: &Payload) -> Result<()> {
+    let len = payload.input.len();
+    unsafe {
+        let input = (entry.reserve.unwrap())(module, len as u32) as usize;
+        // `reserve` may have grown the memory, so it's only looked at afterwards, and `add`
+        // doesn't grow it.
+        let memory = &*(entry.memory.unwrap())(module);
+        let data = std::slice::from_raw_parts_mut(memory.data, memory.size as usize);
+        data[input..input + len].copy_from_slice(&payload.input);
+        let output = (entry.add)(module, len as u32, input as u32) as usize;
+        // Copy the output out, as a host passing it on would.
+        let output = data[output..output + len].to_vec();
+        payload.check(&output)
+    }
+}
+
+/// The module's functions, copied out of a [`State`].
+#[derive(Copy, Clone)]
+struct Entry {
+    add: unsafe extern "C" fn(*mut c_void, u32, u32) -> u32,
+    try_add: unsafe extern "C" fn(*mut c_void, u32, u32, *mut u32) -> u32,
+    instantiate: unsafe extern "C" fn(*mut c_void),
+    free: unsafe extern "C" fn(*mut c_void),
+    reserve: Option<unsafe extern "C" fn(*mut c_void, u32) -> u32>,
+    memory: Option<unsafe extern "C" fn(*mut c_void) -> *mut Memory>,
+}
+
 #[self_referencing]
 pub struct State {
     library: Arc<libloading::Library>,
     memory: Box<[MaybeUninit<u8>]>,
     #[borrows(library)]
     #[covariant]
-    add: libloading::Symbol<'this, unsafe extern "C" fn(*mut std::ffi::c_void, u32, u32)>,
+    add: libloading::Symbol<'this, unsafe extern "C" fn(*mut std::ffi::c_void, u32, u32) -> u32>,
+    #[borrows(library)]
+    #[covariant]
+    try_add: libloading::Symbol<
+        'this,
+        unsafe extern "C" fn(*mut std::ffi::c_void, u32, u32, *mut u32) -> u32,
+    >,
     #[borrows(library)]
     #[covariant]
     instantiate: libloading::Symbol<'this, unsafe extern "C" fn(*mut std::ffi::c_void)>,
     #[borrows(library)]
     #[covariant]
     free: libloading::Symbol<'this, unsafe extern "C" fn(*mut std::ffi::c_void)>,
+    #[borrows(library)]
+    #[covariant]
+    reserve:
+        Option<libloading::Symbol<'this, unsafe extern "C" fn(*mut std::ffi::c_void, u32) -> u32>>,
+    #[borrows(library)]
+    #[covariant]
+    memory: Option<
+        libloading::Symbol<'this, unsafe extern "C" fn(*mut std::ffi::c_void) -> *mut Memory>,
+    >,
+}
+
+impl State {
+    fn entry(&self) -> Entry {
+        Entry {
+            add: **self.borrow_add(),
+            try_add: **self.borrow_try_add(),
+            instantiate: **self.borrow_instantiate(),
+            free: **self.borrow_free(),
+            reserve: self.borrow_reserve().as_ref().map(|reserve| **reserve),
+            memory: self.borrow_memory().as_ref().map(|memory| **memory),
+        }
+    }
 }
 
 impl SimpleRuntime for Wasm2CBenchmark {
     type State = State;
 
-    fn setup(&self) -> Self::State {
+    fn setup(&self) -> Result<Self::State> {
         let library = self.lib.clone();
         unsafe {
-            let module_size: libloading::Symbol<unsafe extern "C" fn() -> usize> =
-                library.get(b"module_size").unwrap();
+            let module_size: libloading::Symbol<unsafe extern "C" fn() -> usize> = library
+                .get(b"module_size")
+                .expect("module_size is defined in lib.c");
             let size = module_size();
+            let init_thread: libloading::Symbol<unsafe extern "C" fn()> =
+                library.get(b"wasm_rt_init_thread").unwrap();
+            init_thread();
             let memory = Box::new_uninit_slice(size);
-            StateBuilder {
+            let mut state = StateBuilder {
                 library,
                 memory,
+                // The export was checked when the library was loaded, and wasm2c and the
+                // import glue always generate the rest.
                 add_builder: |lib| lib.get(b"w2c_module_add").unwrap(),
-                instantiate_builder: |lib| lib.get(b"wasm2c_module_instantiate").unwrap(),
+                try_add_builder: |lib| lib.get(b"module_try_add").unwrap(),
+                instantiate_builder: |lib| lib.get(b"module_instantiate").unwrap(),
                 free_builder: |lib| lib.get(b"wasm2c_module_free").unwrap(),
+                // Only payload workloads export these, which was checked when they were loaded.
+                reserve_builder: |lib| lib.get(b"w2c_module_reserve").ok(),
+                memory_builder: |lib| lib.get(b"w2c_module_memory").ok(),
+            }
+            .build();
+            match self.instantiation {
+                Instantiation::PerCall => {}
+                Instantiation::Reuse => state.with_mut(|fields| {
+                    (fields.instantiate)(fields.memory.as_mut_ptr() as *mut c_void)
+                }),
+                Instantiation::Pooled(reset) => self.set_memory_pool(Some(reset)),
             }
-            .build()
+            Ok(state)
         }
     }
 
-    fn iterate(&self, state: &mut Self::State) {
+    fn iterate(&self, state: &mut Self::State) -> Result<()> {
+        let entry = state.entry();
         state.with_mut(|fields| {
-            let instantiate = fields.instantiate;
-            let add = fields.add;
-            let free = fields.free;
             let module: *mut MaybeUninit<u8> = fields.memory.as_mut_ptr();
             let module = module as *mut std::ffi::c_void;
             unsafe {
-                instantiate(module);
-                add(module, 1, 2);
-                free(module);
+                if self.instantiation == Instantiation::Reuse {
+                    self.call(entry, module)
+                } else {
+                    (entry.instantiate)(module);
+                    let result = self.call(entry, module);
+                    (entry.free)(module);
+                    result
+                }
             }
         })
     }
+
+    fn teardown(&self, mut state: Self::State) -> Result<()> {
+        let entry = state.entry();
+        let recovered = state.with_mut(|fields| unsafe {
+            self.check_recovered(entry, fields.memory.as_mut_ptr() as *mut c_void)
+        });
+        match self.instantiation {
+            Instantiation::PerCall => {}
+            Instantiation::Reuse => state.with_mut(|fields| unsafe {
+                (fields.free)(fields.memory.as_mut_ptr() as *mut c_void)
+            }),
+            Instantiation::Pooled(_) => self.set_memory_pool(None),
+        }
+        unsafe {
+            let free_thread: libloading::Symbol<unsafe extern "C" fn()> =
+                self.lib.get(b"wasm_rt_free_thread").unwrap();
+            free_thread();
+        }
+        recovered
+    }
 }
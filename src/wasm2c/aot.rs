@@ -0,0 +1,212 @@
+use std::{
+    ffi::{CStr, c_char, c_void},
+    mem::MaybeUninit,
+};
+
+use anyhow::Result;
+
+use super::{Instantiation, PoolReset, classify};
+use crate::{Arguments, SimpleRuntime, Trap, TrapCounter};
+
+/// A module linked into the benchmark by the build script (see `aot_module_t` in `build.rs`).
+#[repr(C)]
+struct AotModule {
+    name: *const c_char,
+    size: usize,
+    instantiate: unsafe extern "C" fn(*mut c_void),
+    add: unsafe extern "C" fn(*mut c_void, u32, u32) -> u32,
+    try_add: unsafe extern "C" fn(*mut c_void, u32, u32, *mut u32) -> u32,
+    free: unsafe extern "C" fn(*mut c_void),
+}
+
+// The table is immutable and only holds pointers to static data and functions.
+unsafe impl Sync for AotModule {}
+
+#[cfg(wasm2c_aot)]
+unsafe extern "C" {
+    static aot_modules: [AotModule; 0];
+    static aot_modules_len: usize;
+    fn wasm_rt_init();
+    fn wasm_rt_free();
+    fn wasm_rt_init_thread();
+    fn wasm_rt_free_thread();
+    fn wasm_rt_set_memory_pool(mode: u32) -> bool;
+}
+
+#[cfg(wasm2c_aot)]
+fn modules() -> &'static [AotModule] {
+    unsafe { std::slice::from_raw_parts((&raw const aot_modules).cast(), aot_modules_len) }
+}
+
+#[cfg(not(wasm2c_aot))]
+fn modules() -> &'static [AotModule] {
+    &[]
+}
+
+/// The names of the modules built ahead of time, which is empty if wasm2c wasn't available when
+/// the benchmark was built.
+pub fn aot_modules() -> impl Iterator<Item = &'static str> {
+    modules()
+        .iter()
+        .map(|module| unsafe { CStr::from_ptr(module.name) }.to_str().unwrap())
+}
+
+/// A wasm2c module linked into the benchmark at build time and called directly.
+///
+/// Every such module shares one statically linked runtime, built with guard pages as in
+/// [`RuntimeConfig::MMAP`](super::RuntimeConfig::MMAP).
+pub struct AotBenchmark {
+    module: &'static AotModule,
+    instantiation: Instantiation,
+    traps: Option<TrapCounter>,
+    arguments: Arguments,
+}
+
+impl AotBenchmark {
+    /// Look up the module built from the workload `name`, which is called with `arguments` and
+    /// expected to trap on every call if `traps` is set.
+    pub fn new(
+        name: &str,
+        instantiation: Instantiation,
+        traps: bool,
+        arguments: Arguments,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            cfg!(wasm2c_aot),
+            "wasm2c wasn't available when the benchmark was built, so there are no ahead-of-time modules"
+        );
+        let module = modules()
+            .iter()
+            .zip(aot_modules())
+            .find(|(_, module)| *module == name)
+            .map(|(module, _)| module)
+            .ok_or_else(|| anyhow::anyhow!("no ahead-of-time wasm2c module named `{name}`"))?;
+        #[cfg(wasm2c_aot)]
+        unsafe {
+            wasm_rt_init();
+        }
+        Ok(AotBenchmark {
+            module,
+            instantiation,
+            traps: traps.then(Default::default),
+            arguments,
+        })
+    }
+
+    /// The number of traps of each kind recovered from, if `traps` was set.
+    pub fn trap_counts(&self) -> Vec<(Trap, usize)> {
+        self.traps
+            .as_ref()
+            .map(TrapCounter::counts)
+            .unwrap_or_default()
+    }
+
+    /// Call `add`, catching and counting traps only if the workload is expected to trap.
+    unsafe fn call(&self, module: *mut c_void) -> Result<()> {
+        let Arguments { lhs, rhs, .. } = self.arguments;
+        unsafe {
+            let Some(traps) = &self.traps else {
+                return self.arguments.check((self.module.add)(module, lhs, rhs));
+            };
+            let mut result = 0;
+            match (self.module.try_add)(module, lhs, rhs, &mut result) {
+                0 => anyhow::bail!("expected a trap, but the call returned {result}"),
+                code => traps.record(classify(code)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a call which doesn't trap still succeeds after the traps.
+    unsafe fn check_recovered(&self, module: *mut c_void) -> Result<()> {
+        if self.traps.is_none() {
+            return Ok(());
+        }
+        let mut result = 0;
+        unsafe {
+            if self.instantiation != Instantiation::Reuse {
+                (self.module.instantiate)(module);
+            }
+            let code = (self.module.try_add)(module, 0, 2, &mut result);
+            if self.instantiation != Instantiation::Reuse {
+                (self.module.free)(module);
+            }
+            anyhow::ensure!(
+                (code, result) == (0, 2),
+                "runtime didn't recover from traps"
+            );
+        }
+        Ok(())
+    }
+
+    fn set_memory_pool(&self, _reset: Option<PoolReset>) {
+        #[cfg(wasm2c_aot)]
+        unsafe {
+            let mode = match _reset {
+                None => 0,
+                Some(PoolReset::Madvise) => 1,
+                Some(PoolReset::Zero) => 2,
+            };
+            assert!(wasm_rt_set_memory_pool(mode));
+        }
+    }
+}
+
+impl Drop for AotBenchmark {
+    fn drop(&mut self) {
+        #[cfg(wasm2c_aot)]
+        unsafe {
+            wasm_rt_free();
+        }
+    }
+}
+
+impl SimpleRuntime for AotBenchmark {
+    type State = Box<[MaybeUninit<u8>]>;
+
+    fn setup(&self) -> Result<Self::State> {
+        #[cfg(wasm2c_aot)]
+        unsafe {
+            wasm_rt_init_thread();
+        }
+        let mut state = Box::new_uninit_slice(self.module.size);
+        match self.instantiation {
+            Instantiation::PerCall => {}
+            Instantiation::Reuse => unsafe {
+                (self.module.instantiate)(state.as_mut_ptr() as *mut c_void)
+            },
+            Instantiation::Pooled(reset) => self.set_memory_pool(Some(reset)),
+        }
+        Ok(state)
+    }
+
+    fn iterate(&self, state: &mut Self::State) -> Result<()> {
+        let module = state.as_mut_ptr() as *mut c_void;
+        unsafe {
+            if self.instantiation == Instantiation::Reuse {
+                self.call(module)
+            } else {
+                (self.module.instantiate)(module);
+                let result = self.call(module);
+                (self.module.free)(module);
+                result
+            }
+        }
+    }
+
+    fn teardown(&self, mut state: Self::State) -> Result<()> {
+        let recovered = unsafe { self.check_recovered(state.as_mut_ptr() as *mut c_void) };
+        match self.instantiation {
+            Instantiation::PerCall => {}
+            Instantiation::Reuse => unsafe {
+                (self.module.free)(state.as_mut_ptr() as *mut c_void)
+            },
+            Instantiation::Pooled(_) => self.set_memory_pool(None),
+        }
+        #[cfg(wasm2c_aot)]
+        unsafe {
+            wasm_rt_free_thread();
+        }
+        recovered
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+};
+
+use anyhow::{Context, Result, anyhow};
+use include_directory::Dir;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+use super::error::{WASM2C_VERSION, Wasm2CError, run};
+
+/// A content-addressed cache of compiled wasm2c modules.
+///
+/// Entries are keyed by a hash of everything that goes into building the shared object (see
+/// [`Key`]), so they never need to be invalidated; [`clear`](Cache::clear) only reclaims space.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// The cache in the user's cache directory (`$XDG_CACHE_HOME` or `~/.cache`).
+    pub fn new() -> Result<Self> {
+        let base = std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::home_dir().map(|home| home.join(".cache")))
+            .ok_or_else(|| anyhow!("could not find a cache directory"))?;
+        Ok(Cache {
+            dir: base.join("arca-benchmark").join("wasm2c"),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, key: &Key) -> PathBuf {
+        self.dir.join(format!("{}.so", key.0))
+    }
+
+    /// The cached shared object for `key`, if there is one.
+    pub fn get(&self, key: &Key) -> Option<PathBuf> {
+        let path = self.path(key);
+        path.is_file().then_some(path)
+    }
+
+    /// Copy the shared object at `so` into the cache, returning its new path.
+    ///
+    /// The copy is written to a temporary file and renamed into place, so concurrent runs
+    /// building the same module never see a partially written entry.
+    pub fn insert(&self, key: &Key, so: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("could not create {}", self.dir.display()))?;
+        let mut temp = tempfile::NamedTempFile::new_in(&self.dir)?;
+        std::io::copy(&mut std::fs::File::open(so)?, temp.as_file_mut())?;
+        let path = self.path(key);
+        temp.persist(&path)?;
+        Ok(path)
+    }
+
+    /// Remove every cached module.
+    pub fn clear(&self) -> Result<()> {
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("could not remove {}", self.dir.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Cache entries loaded by a live [`SharedObject`].
+static LOADED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// A shared object to load, kept alive for as long as the module is loaded.
+///
+/// `dlopen` hands out the same handle for the same path, so two modules loaded from one cache
+/// entry would share the runtime's global state, and the first freed would free it under the
+/// other. Only the first concurrent load of an entry uses it in place; the rest load a private
+/// copy.
+pub struct SharedObject {
+    path: PathBuf,
+    /// The temporary directory holding a private copy, which nothing else loads.
+    dir: Option<TempDir>,
+}
+
+impl SharedObject {
+    /// Load the cache entry at `path`, or a copy of it if it's already loaded.
+    pub fn cached(path: PathBuf) -> Result<Self> {
+        if LOADED.lock().unwrap().insert(path.clone()) {
+            return Ok(SharedObject { path, dir: None });
+        }
+        let dir = tempfile::tempdir()?;
+        let copy = dir.path().join("module.so");
+        std::fs::copy(&path, &copy)?;
+        Ok(SharedObject::private(dir, copy))
+    }
+
+    /// Load `path`, in `dir`, which nothing else loads.
+    pub fn private(dir: TempDir, path: PathBuf) -> Self {
+        SharedObject {
+            path,
+            dir: Some(dir),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SharedObject {
+    fn drop(&mut self) {
+        if self.dir.is_none() {
+            LOADED.lock().unwrap().remove(&self.path);
+        }
+    }
+}
+
+/// The hash identifying a compiled module.
+pub struct Key(String);
+
+impl Key {
+    /// Hash the wasm bytes, the import glue, the runtime sources, the wasm2c version, the identity
+    /// of the C compiler, and the compiler flags.
+    ///
+    /// Modules are only built with the wasm2c version the runtime is bundled for, so that's hashed
+    /// rather than asking the installed `wasm2c`, which a cache hit never runs.
+    pub fn new(wasm: &[u8], glue: &str, runtime: &Dir, cc: &str, flags: &[String]) -> Result<Self> {
+        let mut hasher = Sha256::new();
+        // Every field is length-prefixed so that adjacent fields can't run into each other.
+        let mut field = |bytes: &[u8]| {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        field(wasm);
+        field(glue.as_bytes());
+        let mut files: Vec<_> = runtime.files().collect();
+        files.sort_by_key(|file| file.path());
+        for file in files {
+            field(file.path().as_os_str().as_encoded_bytes());
+            field(file.contents());
+        }
+        field(WASM2C_VERSION.as_bytes());
+        field(&identity(cc)?);
+        for flag in flags {
+            field(flag.as_bytes());
+        }
+        let hash = hasher.finalize();
+        Ok(Key(hash.iter().map(|b| format!("{b:02x}")).collect()))
+    }
+}
+
+/// The version string a tool reports, which stands in for its identity.
+fn identity(tool: &str) -> Result<Vec<u8>, Wasm2CError> {
+    Ok(run(Command::new(tool).arg("--version"))?.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{CompilerConfig, RuntimeConfig, WASM2C_RT};
+    use super::*;
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn key(wasm: &[u8], cc: &str, flags: &[String]) -> String {
+        Key::new(wasm, "", &WASM2C_RT, cc, flags).unwrap().0
+    }
+
+    fn flags(runtime: RuntimeConfig, compiler: &CompilerConfig) -> Vec<String> {
+        let mut flags = runtime.flags();
+        flags.extend(compiler.flags());
+        flags
+    }
+
+    #[test]
+    fn key_is_stable() {
+        let flags = flags(RuntimeConfig::MMAP, &CompilerConfig::default());
+        assert_eq!(key(WASM, "cc", &flags), key(WASM, "cc", &flags));
+    }
+
+    #[test]
+    fn key_changes_with_every_input() {
+        let compiler = CompilerConfig::default();
+        let default = flags(RuntimeConfig::MMAP, &compiler);
+        let base = key(WASM, "cc", &default);
+
+        assert_ne!(key(b"\0asm\x01\0\0\0\0", "cc", &default), base, "wasm");
+        // Any other tool reports a different version, as a different compiler would.
+        assert_ne!(key(WASM, "rustc", &default), base, "compiler");
+        let lto = CompilerConfig {
+            lto: true,
+            ..compiler.clone()
+        };
+        assert_ne!(
+            key(WASM, "cc", &flags(RuntimeConfig::MMAP, &lto)),
+            base,
+            "flags"
+        );
+        let runtime = flags(RuntimeConfig::BOUNDS_CHECKED, &compiler);
+        assert_ne!(key(WASM, "cc", &runtime), base, "runtime config");
+        assert_ne!(
+            Key::new(WASM, "/* glue */", &WASM2C_RT, "cc", &default)
+                .unwrap()
+                .0,
+            base,
+            "glue"
+        );
+    }
+
+    #[test]
+    fn key_separates_fields() {
+        let split = ["-Da".into(), "=1".into()];
+        assert_ne!(key(WASM, "cc", &split), key(WASM, "cc", &["-Da=1".into()]));
+    }
+}
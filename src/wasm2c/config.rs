@@ -0,0 +1,347 @@
+use anyhow::Result;
+
+use crate::{Arguments, Trap};
+
+/// Which of the runtime's safety mechanisms a module is built with.
+///
+/// Each field corresponds to one of the knobs in `wasm-rt.h`, so that the cost of each mechanism
+/// can be measured on its own. The header rejects some combinations;
+/// [`validate`](RuntimeConfig::validate) checks for them up front so they're reported before
+/// anything is compiled.
+///
+/// Segment-register (segue) bounds checking isn't offered: the bundled 1.0.34 runtime predates
+/// `WASM_RT_ALLOW_SEGUE`, so modules can't be built with it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Allocate linear memory with `mmap` rather than `calloc`/`realloc` (`WASM_RT_USE_MMAP`).
+    pub use_mmap: bool,
+    /// Reserve 8 GiB per memory so that out-of-bounds accesses hit unmapped pages
+    /// (`WASM_RT_MEMCHECK_GUARD_PAGES`).
+    pub guard_pages: bool,
+    /// Check every memory access explicitly (`WASM_RT_MEMCHECK_BOUNDS_CHECK`).
+    pub bounds_check: bool,
+    /// Count call depth to detect stack exhaustion (`WASM_RT_USE_STACK_DEPTH_COUNT`).
+    pub stack_depth_count: bool,
+    /// Never install a signal handler, so faults abort the process
+    /// (`WASM_RT_SKIP_SIGNAL_RECOVERY`).
+    pub skip_signal_recovery: bool,
+    /// Turn SIGSEGV and SIGBUS into traps (`WASM_RT_INSTALL_SIGNAL_HANDLER`).
+    pub install_signal_handler: bool,
+}
+
+impl RuntimeConfig {
+    /// `calloc`ed memory with explicit bounds checks and stack depth counting.
+    pub const BOUNDS_CHECKED: Self = RuntimeConfig {
+        use_mmap: false,
+        guard_pages: false,
+        bounds_check: true,
+        stack_depth_count: true,
+        skip_signal_recovery: false,
+        install_signal_handler: false,
+    };
+
+    /// Guard pages, with a signal handler catching out-of-bounds accesses and stack overflows.
+    pub const MMAP: Self = RuntimeConfig {
+        use_mmap: true,
+        guard_pages: true,
+        bounds_check: false,
+        stack_depth_count: false,
+        skip_signal_recovery: false,
+        install_signal_handler: true,
+    };
+
+    /// `mmap`ed memory sized to the module's maximum, with explicit bounds checks.
+    pub const MMAP_BOUNDS_CHECKED: Self = RuntimeConfig {
+        use_mmap: true,
+        ..Self::BOUNDS_CHECKED
+    };
+
+    /// Explicit bounds checks, with the signal handler rather than depth counting catching stack
+    /// overflows.
+    pub const BOUNDS_CHECKED_SIGNAL: Self = RuntimeConfig {
+        stack_depth_count: false,
+        install_signal_handler: true,
+        ..Self::BOUNDS_CHECKED
+    };
+
+    /// Guard pages, counting stack depth as well as installing the signal handler.
+    pub const MMAP_STACK_DEPTH: Self = RuntimeConfig {
+        stack_depth_count: true,
+        ..Self::MMAP
+    };
+
+    /// Guard pages with no signal handler, so that an out-of-bounds access crashes.
+    pub const MMAP_NO_RECOVERY: Self = RuntimeConfig {
+        stack_depth_count: true,
+        skip_signal_recovery: true,
+        install_signal_handler: false,
+        ..Self::MMAP
+    };
+
+    /// Check for the combinations `wasm-rt.h` refuses to build.
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            self.guard_pages != self.bounds_check,
+            "exactly one of guard pages and bounds checking must be used"
+        );
+        anyhow::ensure!(
+            !self.guard_pages || self.use_mmap,
+            "guard pages need mmap-allocated memory"
+        );
+        anyhow::ensure!(
+            !(self.install_signal_handler && self.skip_signal_recovery),
+            "the signal handler can't be installed when skipping signal recovery"
+        );
+        Ok(())
+    }
+
+    /// Whether a module built with this configuration recovers from `trap` rather than crashing.
+    pub fn recovers(&self, trap: Trap) -> bool {
+        match trap {
+            Trap::OutOfBounds => self.bounds_check || self.install_signal_handler,
+            Trap::StackOverflow => self.stack_depth_count || self.install_signal_handler,
+            _ => true,
+        }
+    }
+
+    /// The compiler flags selecting this configuration.
+    pub fn flags(&self) -> Vec<String> {
+        let define = |name: &str, value: bool| format!("-D{name}={}", value as u8);
+        vec![
+            define("WASM_RT_USE_MMAP", self.use_mmap),
+            define("WASM_RT_MEMCHECK_GUARD_PAGES", self.guard_pages),
+            define("WASM_RT_MEMCHECK_BOUNDS_CHECK", self.bounds_check),
+            define("WASM_RT_USE_STACK_DEPTH_COUNT", self.stack_depth_count),
+            define("WASM_RT_SKIP_SIGNAL_RECOVERY", self.skip_signal_recovery),
+            define(
+                "WASM_RT_INSTALL_SIGNAL_HANDLER",
+                self.install_signal_handler,
+            ),
+        ]
+    }
+}
+
+/// How the generated C is compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilerConfig {
+    /// The C compiler to run, e.g. `gcc` or `clang`.
+    pub cc: String,
+    /// The optimisation level, passed as `-O<level>`.
+    pub opt_level: String,
+    /// The target CPU, passed as `-march=<cpu>`.
+    pub march: Option<String>,
+    /// Link with `-flto`, letting the runtime be inlined into the module.
+    pub lto: bool,
+    /// Pass `-frounding-math -fsignaling-nans`, which wasm2c needs for exact wasm float semantics.
+    pub fp_strict: bool,
+    /// Any further flags, passed last.
+    pub extra_flags: Vec<String>,
+}
+
+impl Default for CompilerConfig {
+    fn default() -> Self {
+        CompilerConfig {
+            cc: "cc".into(),
+            opt_level: "2".into(),
+            march: None,
+            lto: false,
+            fp_strict: true,
+            extra_flags: vec![],
+        }
+    }
+}
+
+impl CompilerConfig {
+    /// The flags for compiling a module into a shared object.
+    pub fn flags(&self) -> Vec<String> {
+        // Wasm never fuses a multiply and an add, so neither may the compiler, or float results
+        // (which workloads such as n-body are checked on) depend on whether `march` has FMA.
+        let mut flags: Vec<String> = [
+            "-lm",
+            "-fPIC",
+            "-shared",
+            "-fno-optimize-sibling-calls",
+            "-ffp-contract=off",
+        ]
+        .map(String::from)
+        .into();
+        flags.push(format!("-O{}", self.opt_level));
+        if let Some(march) = &self.march {
+            flags.push(format!("-march={march}"));
+        }
+        if self.lto {
+            flags.push("-flto".into());
+        }
+        if self.fp_strict {
+            flags.extend(["-frounding-math".into(), "-fsignaling-nans".into()]);
+        }
+        flags.extend(self.extra_flags.iter().cloned());
+        flags
+    }
+}
+
+/// Settings a benchmark mode changes in the [`CompilerConfig`] it's given, so that compilers and
+/// flags can be compared side by side in one run.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CompilerOverride {
+    pub cc: Option<&'static str>,
+    pub opt_level: Option<&'static str>,
+    pub march: Option<&'static str>,
+    pub lto: Option<bool>,
+    pub fp_strict: Option<bool>,
+}
+
+impl CompilerOverride {
+    /// Change nothing.
+    pub const NONE: Self = CompilerOverride {
+        cc: None,
+        opt_level: None,
+        march: None,
+        lto: None,
+        fp_strict: None,
+    };
+
+    /// `config` with the settings this overrides replaced. Extra flags are kept.
+    pub fn apply(&self, config: &CompilerConfig) -> CompilerConfig {
+        CompilerConfig {
+            cc: self.cc.map_or_else(|| config.cc.clone(), String::from),
+            opt_level: self
+                .opt_level
+                .map_or_else(|| config.opt_level.clone(), String::from),
+            march: self
+                .march
+                .map(String::from)
+                .or_else(|| config.march.clone()),
+            lto: self.lto.unwrap_or(config.lto),
+            fp_strict: self.fp_strict.unwrap_or(config.fp_strict),
+            extra_flags: config.extra_flags.clone(),
+        }
+    }
+}
+
+/// How a freed linear memory is reset before being reused from the pool.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PoolReset {
+    /// Drop the pages with `madvise(MADV_DONTNEED)`, so the kernel supplies zero pages on the next
+    /// touch.
+    Madvise,
+    /// Zero the pages eagerly with `memset`.
+    Zero,
+}
+
+/// How instances are created for each call.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Instantiation {
+    /// Instantiate and free the module on every call.
+    #[default]
+    PerCall,
+    /// Instantiate the module once per thread and only call the export.
+    Reuse,
+    /// Instantiate the module on every call, but recycle linear memory reservations from a
+    /// per-thread pool rather than mapping a fresh one each time (needs `use_mmap`).
+    Pooled(PoolReset),
+}
+
+/// Everything controlling how a wasm2c benchmark is built and run.
+#[derive(Debug, Clone)]
+pub struct Wasm2CConfig {
+    pub runtime: RuntimeConfig,
+    pub compiler: CompilerConfig,
+    pub instantiation: Instantiation,
+    /// Reuse previously compiled modules from the [`Cache`](super::Cache).
+    pub cache: bool,
+    /// Expect every call to trap: catch the traps and count them by kind.
+    pub traps: bool,
+    /// What to call `add` with.
+    pub arguments: Arguments,
+}
+
+impl Wasm2CConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.runtime.validate()?;
+        anyhow::ensure!(
+            !matches!(self.instantiation, Instantiation::Pooled(_)) || self.runtime.use_mmap,
+            "memory pooling needs mmap-allocated memory"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [RuntimeConfig; 6] = [
+        RuntimeConfig::BOUNDS_CHECKED,
+        RuntimeConfig::MMAP,
+        RuntimeConfig::MMAP_BOUNDS_CHECKED,
+        RuntimeConfig::BOUNDS_CHECKED_SIGNAL,
+        RuntimeConfig::MMAP_STACK_DEPTH,
+        RuntimeConfig::MMAP_NO_RECOVERY,
+    ];
+
+    #[test]
+    fn modes_are_valid() {
+        for mode in MODES {
+            mode.validate().unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_rejects_what_the_header_does() {
+        let rejected = |config: RuntimeConfig| config.validate().unwrap_err().to_string();
+        let neither = RuntimeConfig {
+            bounds_check: false,
+            ..RuntimeConfig::BOUNDS_CHECKED
+        };
+        let both = RuntimeConfig {
+            bounds_check: true,
+            ..RuntimeConfig::MMAP
+        };
+        for config in [neither, both] {
+            assert_eq!(
+                rejected(config),
+                "exactly one of guard pages and bounds checking must be used"
+            );
+        }
+        let calloc = RuntimeConfig {
+            use_mmap: false,
+            ..RuntimeConfig::MMAP
+        };
+        assert_eq!(rejected(calloc), "guard pages need mmap-allocated memory");
+        let skipped = RuntimeConfig {
+            skip_signal_recovery: true,
+            ..RuntimeConfig::MMAP
+        };
+        assert_eq!(
+            rejected(skipped),
+            "the signal handler can't be installed when skipping signal recovery"
+        );
+    }
+
+    #[test]
+    fn flags_define_every_knob() {
+        assert_eq!(
+            RuntimeConfig::MMAP_NO_RECOVERY.flags(),
+            [
+                "-DWASM_RT_USE_MMAP=1",
+                "-DWASM_RT_MEMCHECK_GUARD_PAGES=1",
+                "-DWASM_RT_MEMCHECK_BOUNDS_CHECK=0",
+                "-DWASM_RT_USE_STACK_DEPTH_COUNT=1",
+                "-DWASM_RT_SKIP_SIGNAL_RECOVERY=1",
+                "-DWASM_RT_INSTALL_SIGNAL_HANDLER=0",
+            ]
+        );
+        assert_eq!(
+            RuntimeConfig::BOUNDS_CHECKED.flags(),
+            [
+                "-DWASM_RT_USE_MMAP=0",
+                "-DWASM_RT_MEMCHECK_GUARD_PAGES=0",
+                "-DWASM_RT_MEMCHECK_BOUNDS_CHECK=1",
+                "-DWASM_RT_USE_STACK_DEPTH_COUNT=1",
+                "-DWASM_RT_SKIP_SIGNAL_RECOVERY=0",
+                "-DWASM_RT_INSTALL_SIGNAL_HANDLER=0",
+            ]
+        );
+    }
+}
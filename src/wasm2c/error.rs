@@ -0,0 +1,112 @@
+use std::{
+    fmt::Write,
+    process::{Command, ExitStatus, Output},
+};
+
+/// The version of wasm2c which the runtime files in `wasm2c/` come from, as set by the build
+/// script.
+pub const WASM2C_VERSION: &str = env!("WASM2C_VERSION");
+
+/// Why a wasm2c module couldn't be built or loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum Wasm2CError {
+    #[error("`{tool}` was not found on PATH")]
+    MissingTool { tool: String },
+    #[error("could not run `{command}`")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("`{command}` failed ({status}){}", output(.stdout, .stderr))]
+    Failed {
+        command: String,
+        status: ExitStatus,
+        stdout: String,
+        stderr: String,
+    },
+    #[error(
+        "found wasm2c {found}, but the bundled runtime is from wasm2c {}",
+        WASM2C_VERSION
+    )]
+    WrongVersion { found: String },
+    #[error("could not parse module")]
+    Parse(#[from] wasmparser::BinaryReaderError),
+    #[error("import `{module}.{name}` isn't a host function wasm2c modules can import")]
+    UnsupportedImport { module: String, name: String },
+    #[error("import `{module}.{name}` has type {found}, but the host function has type {expected}")]
+    ImportMismatch {
+        module: String,
+        name: String,
+        found: String,
+        expected: String,
+    },
+    #[error("module doesn't export `{name}`")]
+    MissingExport {
+        name: String,
+        #[source]
+        source: libloading::Error,
+    },
+}
+
+fn output(stdout: &str, stderr: &str) -> String {
+    let mut output = String::new();
+    for (name, text) in [("stdout", stdout), ("stderr", stderr)] {
+        let text = text.trim_end();
+        if !text.is_empty() {
+            write!(output, "\n{name}:\n{text}").unwrap();
+        }
+    }
+    output
+}
+
+/// The command line `command` runs, as it would be typed into a shell.
+fn command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("'{arg}'")
+            } else {
+                arg.into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run `command` to completion, capturing its output and failing if it doesn't succeed.
+pub fn run(command: &mut Command) -> Result<Output, Wasm2CError> {
+    let output = command.output().map_err(|source| {
+        if source.kind() == std::io::ErrorKind::NotFound {
+            Wasm2CError::MissingTool {
+                tool: command.get_program().to_string_lossy().into_owned(),
+            }
+        } else {
+            Wasm2CError::Spawn {
+                command: command_line(command),
+                source,
+            }
+        }
+    })?;
+    if !output.status.success() {
+        return Err(Wasm2CError::Failed {
+            command: command_line(command),
+            status: output.status,
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    Ok(output)
+}
+
+/// Check that the installed wasm2c matches the bundled runtime.
+pub fn check_version() -> Result<(), Wasm2CError> {
+    let output = run(Command::new("wasm2c").arg("--version"))?;
+    let found = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if found != WASM2C_VERSION {
+        return Err(Wasm2CError::WrongVersion { found });
+    }
+    Ok(())
+}
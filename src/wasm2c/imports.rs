@@ -0,0 +1,294 @@
+use std::{ffi::c_void, fmt::Write};
+
+use wasmparser::{FuncType, Parser, Payload, TypeRef, ValType};
+
+use super::error::Wasm2CError;
+
+/// A Rust function which wasm2c modules can import.
+#[derive(Debug, Copy, Clone)]
+pub struct HostFunction {
+    pub module: &'static str,
+    pub name: &'static str,
+    pub params: &'static [ValType],
+    pub results: &'static [ValType],
+    /// An `extern "C"` function taking the importing module's instance pointer (always null)
+    /// followed by the wasm parameters.
+    pub function: *const c_void,
+}
+
+extern "C" fn add(_instance: *mut c_void, lhs: u32, rhs: u32) -> u32 {
+    lhs.wrapping_add(rhs)
+}
+
+/// The host functions every module may import.
+pub fn host_functions() -> Vec<HostFunction> {
+    vec![HostFunction {
+        module: "host",
+        name: "add",
+        params: &[ValType::I32, ValType::I32],
+        results: &[ValType::I32],
+        function: add as *const c_void,
+    }]
+}
+
+/// A module's function imports, resolved against the host functions, and the C glue binding
+/// them.
+///
+/// wasm2c declares each import as an external function (`w2c_<module>_<name>`) and adds a
+/// parameter to `wasm2c_module_instantiate` for each imported module's instance. The glue defines
+/// each import as a call through a function pointer set by [`bind`](Imports::bind), and wraps
+/// instantiation as `module_instantiate`, so that modules with and without imports are driven
+/// the same way.
+pub struct Imports {
+    functions: Vec<HostFunction>,
+    glue: String,
+}
+
+impl Imports {
+    pub fn resolve(wasm: &[u8], host: &[HostFunction]) -> Result<Self, Wasm2CError> {
+        let mut types = vec![];
+        let mut functions = vec![];
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload? {
+                Payload::TypeSection(reader) => {
+                    for ty in reader.into_iter_err_on_gc_types() {
+                        types.push(ty?);
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import?;
+                        let unsupported = || Wasm2CError::UnsupportedImport {
+                            module: import.module.into(),
+                            name: import.name.into(),
+                        };
+                        let TypeRef::Func(index) = import.ty else {
+                            return Err(unsupported());
+                        };
+                        let ty: &FuncType = &types[index as usize];
+                        let function = host
+                            .iter()
+                            .find(|f| f.module == import.module && f.name == import.name)
+                            .ok_or_else(unsupported)?;
+                        if function.params != ty.params() || function.results != ty.results() {
+                            return Err(Wasm2CError::ImportMismatch {
+                                module: import.module.into(),
+                                name: import.name.into(),
+                                found: signature(ty.params(), ty.results()),
+                                expected: signature(function.params, function.results),
+                            });
+                        }
+                        // wasm2c declares one C function per (module, name), however many
+                        // times it's imported.
+                        let imported = |f: &HostFunction| {
+                            f.module == function.module && f.name == function.name
+                        };
+                        if !functions.iter().any(imported) {
+                            functions.push(*function);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let glue = glue(&functions)?;
+        Ok(Imports { functions, glue })
+    }
+
+    /// The C source defining the imports and `module_instantiate`.
+    pub fn glue(&self) -> &str {
+        &self.glue
+    }
+
+    /// Point the imports of a loaded module at their host functions.
+    ///
+    /// # Safety
+    ///
+    /// `lib` must have been built with this module's glue.
+    pub unsafe fn bind(&self, lib: &libloading::Library) {
+        unsafe {
+            let set_import: libloading::Symbol<unsafe extern "C" fn(u32, *const c_void)> = lib
+                .get(b"module_set_import")
+                .expect("module_set_import is defined by the glue");
+            for (i, function) in self.functions.iter().enumerate() {
+                set_import(i as u32, function.function);
+            }
+        }
+    }
+}
+
+fn signature(params: &[ValType], results: &[ValType]) -> String {
+    let list = |types: &[ValType]| {
+        let types: Vec<_> = types.iter().map(ToString::to_string).collect();
+        types.join(", ")
+    };
+    format!("({}) -> ({})", list(params), list(results))
+}
+
+/// The name wasm2c gives a module or field in C identifiers, for names it leaves unchanged.
+fn mangle(name: &str) -> Option<&str> {
+    // wasm2c escapes some characters (and its escape character itself), so only accept names
+    // which are certain to come through as they are.
+    let plain = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    plain.then_some(name)
+}
+
+fn c_type(ty: ValType) -> Option<&'static str> {
+    match ty {
+        ValType::I32 => Some("u32"),
+        ValType::I64 => Some("u64"),
+        ValType::F32 => Some("f32"),
+        ValType::F64 => Some("f64"),
+        _ => None,
+    }
+}
+
+fn glue(functions: &[HostFunction]) -> Result<String, Wasm2CError> {
+    let mut modules: Vec<&str> = functions.iter().map(|f| f.module).collect();
+    // wasm2c orders the instantiate parameters by module name.
+    modules.sort();
+    modules.dedup();
+
+    let mut c = String::new();
+    writeln!(c, "#include \"module.h\"\n").unwrap();
+    writeln!(c, "static void* imports[{}];\n", functions.len().max(1)).unwrap();
+    writeln!(c, "void module_set_import(u32 index, void* pointer) {{").unwrap();
+    writeln!(c, "  imports[index] = pointer;").unwrap();
+    writeln!(c, "}}\n").unwrap();
+    for (i, function) in functions.iter().enumerate() {
+        let unsupported = || Wasm2CError::UnsupportedImport {
+            module: function.module.into(),
+            name: function.name.into(),
+        };
+        let (Some(module), Some(name)) = (mangle(function.module), mangle(function.name)) else {
+            return Err(unsupported());
+        };
+        let result = match function.results {
+            [] => "void",
+            [ty] => c_type(*ty).ok_or_else(unsupported)?,
+            _ => return Err(unsupported()),
+        };
+        let instance = format!("struct w2c_{module}*");
+        let mut params = vec![instance.clone()];
+        let mut args = vec!["instance".to_string()];
+        let mut decls = vec![format!("{instance} instance")];
+        for (j, ty) in function.params.iter().enumerate() {
+            let ty = c_type(*ty).ok_or_else(unsupported)?;
+            params.push(ty.into());
+            args.push(format!("a{j}"));
+            decls.push(format!("{ty} a{j}"));
+        }
+        let params = params.join(", ");
+        let args = args.join(", ");
+        let decls = decls.join(", ");
+        writeln!(c, "{result} w2c_{module}_{name}({decls}) {{").unwrap();
+        let ret = if result == "void" { "" } else { "return " };
+        writeln!(c, "  {ret}(({result} (*)({params}))imports[{i}])({args});").unwrap();
+        writeln!(c, "}}\n").unwrap();
+    }
+    write!(
+        c,
+        "void module_instantiate(w2c_module* module) {{\n  wasm2c_module_instantiate(module"
+    )
+    .unwrap();
+    for _ in &modules {
+        write!(c, ", NULL").unwrap();
+    }
+    writeln!(c, ");\n}}").unwrap();
+    Ok(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(
+        name: &'static str,
+        params: &'static [ValType],
+        results: &'static [ValType],
+    ) -> HostFunction {
+        HostFunction {
+            module: "host",
+            name,
+            params,
+            results,
+            function: std::ptr::null(),
+        }
+    }
+
+    fn unsupported(result: Result<impl std::fmt::Debug, Wasm2CError>) -> bool {
+        matches!(result, Err(Wasm2CError::UnsupportedImport { .. }))
+    }
+
+    #[test]
+    fn glue_binds_each_supported_signature() {
+        use ValType::*;
+        let functions = [
+            function("none", &[], &[]),
+            function("i32", &[I32], &[I32]),
+            function("i64", &[I64, I32], &[I64]),
+            function("f32", &[F32], &[F32]),
+            function("f64", &[F64, F64], &[F64]),
+        ];
+        let c = glue(&functions).unwrap();
+        assert!(c.contains("static void* imports[5];"));
+        for line in [
+            "void w2c_host_none(struct w2c_host* instance) {",
+            "  ((void (*)(struct w2c_host*))imports[0])(instance);",
+            "u32 w2c_host_i32(struct w2c_host* instance, u32 a0) {",
+            "  return ((u32 (*)(struct w2c_host*, u32))imports[1])(instance, a0);",
+            "u64 w2c_host_i64(struct w2c_host* instance, u64 a0, u32 a1) {",
+            "  return ((u64 (*)(struct w2c_host*, u64, u32))imports[2])(instance, a0, a1);",
+            "f32 w2c_host_f32(struct w2c_host* instance, f32 a0) {",
+            "f64 w2c_host_f64(struct w2c_host* instance, f64 a0, f64 a1) {",
+        ] {
+            assert!(c.lines().any(|l| l == line), "no `{line}` in:\n{c}");
+        }
+        // One instance parameter for the one imported module.
+        assert!(c.contains("wasm2c_module_instantiate(module, NULL);"));
+    }
+
+    #[test]
+    fn glue_without_imports() {
+        let c = glue(&[]).unwrap();
+        assert!(c.contains("static void* imports[1];"));
+        assert!(c.contains("wasm2c_module_instantiate(module);"));
+    }
+
+    #[test]
+    fn glue_rejects_unsupported_signatures() {
+        use ValType::*;
+        assert!(unsupported(glue(&[function("v128", &[V128], &[])])));
+        assert!(unsupported(glue(&[function("v128", &[], &[V128])])));
+        assert!(unsupported(glue(&[function("pair", &[], &[I32, I32])])));
+        // Names wasm2c would escape.
+        assert!(unsupported(glue(&[function("Add", &[], &[])])));
+        assert!(unsupported(glue(&[function("add_one", &[], &[])])));
+    }
+
+    #[test]
+    fn resolve_rejects_unsupported_imports() {
+        let resolve = |wat: &str| {
+            let wasm = crate::wasm_binary(wat.as_bytes()).unwrap();
+            Imports::resolve(&wasm, &host_functions()).map(|imports| imports.functions.len())
+        };
+        assert_eq!(
+            resolve(r#"(module (import "host" "add" (func (param i32 i32) (result i32))))"#)
+                .unwrap(),
+            1
+        );
+        assert!(unsupported(resolve(
+            r#"(module (import "host" "memory" (memory 1)))"#
+        )));
+        assert!(unsupported(resolve(
+            r#"(module (import "host" "sub" (func)))"#
+        )));
+        assert!(matches!(
+            resolve(r#"(module (import "host" "add" (func (param i64 i64) (result i64))))"#),
+            Err(Wasm2CError::ImportMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use wasmi::{Engine, Linker, Memory, Module, Store, TypedFunc, core::TrapCode};
+
+use crate::{
+    Arguments, Trap,
+    embedded::{EmbeddedBenchmark, Embedding},
+    wasm_binary,
+};
+
+/// How a [`WasmiBenchmark`] instantiates its module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WasmiMode {
+    /// A new instance for every call.
+    PerCall,
+    /// One instance per thread, reused for every call.
+    Reuse,
+}
+
+pub type WasmiBenchmark = EmbeddedBenchmark<Wasmi>;
+
+impl WasmiBenchmark {
+    /// Translate `module` (in text or binary format) to wasmi's bytecode. It's called with
+    /// `arguments` and expected to trap on every call if `traps` is set.
+    ///
+    /// The module must export `add`, and for payload workloads the other exports described by
+    /// [`crate::Payload`]. It may import `host.add`. wasmi doesn't support SIMD.
+    pub fn new(module: &[u8], mode: WasmiMode, traps: bool, arguments: Arguments) -> Result<Self> {
+        let wasm = wasm_binary(module)?;
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm)?;
+        let mut linker = Linker::new(&engine);
+        linker.func_wrap("host", "add", |lhs: u32, rhs: u32| lhs.wrapping_add(rhs))?;
+        let wasmi = Wasmi {
+            engine,
+            module,
+            linker,
+        };
+        Ok(Self::from_embedding(
+            wasmi,
+            mode == WasmiMode::Reuse,
+            traps,
+            arguments,
+        ))
+    }
+}
+
+/// wasmi, which interprets modules rather than compiling them to native code: quick to start,
+/// slow to run, and needing no C compiler.
+pub struct Wasmi {
+    engine: Engine,
+    module: Module,
+    linker: Linker<()>,
+}
+
+/// An instance, with its store and the exports the benchmark calls.
+pub struct Instance {
+    store: Store<()>,
+    add: TypedFunc<(u32, u32), u32>,
+    /// `reserve` and the memory, for payload workloads.
+    transfer: Option<(TypedFunc<u32, u32>, Memory)>,
+}
+
+impl Instance {
+    /// The store, `reserve` and the memory, for payload workloads.
+    fn transfer(&mut self) -> (&mut Store<()>, &TypedFunc<u32, u32>, Memory) {
+        let (reserve, memory) = self
+            .transfer
+            .as_ref()
+            .expect("payload workloads export `reserve`");
+        (&mut self.store, reserve, *memory)
+    }
+}
+
+impl Embedding for Wasmi {
+    type Instance = Instance;
+    type Error = wasmi::Error;
+
+    fn instantiate(&self, payload: bool) -> Result<Instance> {
+        let mut store = Store::new(&self.engine, ());
+        let instance = self
+            .linker
+            .instantiate(&mut store, &self.module)?
+            .start(&mut store)?;
+        let add = instance.get_typed_func(&store, "add")?;
+        let transfer = match payload {
+            true => Some((
+                instance.get_typed_func(&store, "reserve")?,
+                instance
+                    .get_memory(&store, "memory")
+                    .context("payload workloads must export their memory")?,
+            )),
+            false => None,
+        };
+        Ok(Instance {
+            store,
+            add,
+            transfer,
+        })
+    }
+
+    fn add(instance: &mut Instance, lhs: u32, rhs: u32) -> Result<u32, wasmi::Error> {
+        instance.add.call(&mut instance.store, (lhs, rhs))
+    }
+
+    fn classify(error: &wasmi::Error) -> Trap {
+        match error.as_trap_code() {
+            Some(TrapCode::MemoryOutOfBounds) => Trap::OutOfBounds,
+            Some(TrapCode::UnreachableCodeReached) => Trap::Unreachable,
+            Some(TrapCode::IntegerDivisionByZero) => Trap::DivideByZero,
+            Some(TrapCode::StackOverflow) => Trap::StackOverflow,
+            _ => Trap::Other,
+        }
+    }
+
+    fn reserve(instance: &mut Instance, len: u32) -> Result<u32> {
+        let (store, reserve, _) = instance.transfer();
+        Ok(reserve.call(store, len)?)
+    }
+
+    fn write(instance: &mut Instance, offset: usize, bytes: &[u8]) -> Result<()> {
+        let (store, _, memory) = instance.transfer();
+        memory
+            .write(store, offset, bytes)
+            .map_err(wasmi::Error::from)?;
+        Ok(())
+    }
+
+    fn read(instance: &mut Instance, offset: usize, bytes: &mut [u8]) -> Result<()> {
+        let (store, _, memory) = instance.transfer();
+        memory
+            .read(store, offset, bytes)
+            .map_err(wasmi::Error::from)?;
+        Ok(())
+    }
+}
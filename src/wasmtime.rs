@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use wasmtime::{
+    Config, Engine, InstanceAllocationStrategy, InstancePre, Linker, Memory, Module,
+    PoolingAllocationConfig, Store, TypedFunc,
+};
+
+use crate::{
+    Arguments, Trap,
+    embedded::{EmbeddedBenchmark, Embedding},
+    wasm_binary,
+};
+
+/// How a [`WasmtimeBenchmark`] instantiates its module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WasmtimeMode {
+    /// A new instance for every call, from the default on-demand allocator.
+    OnDemand,
+    /// A new instance for every call, from the pooling allocator, which keeps memory slots
+    /// mapped between instances and initialises them copy-on-write.
+    Pooling,
+    /// One instance per thread, reused for every call.
+    Reuse,
+}
+
+pub type WasmtimeBenchmark = EmbeddedBenchmark<Wasmtime>;
+
+impl WasmtimeBenchmark {
+    /// Compile `module` (in text or binary format), which is called with `arguments` and expected
+    /// to trap on every call if `traps` is set.
+    ///
+    /// The module must export `add`, and for payload workloads the other exports described by
+    /// [`crate::Payload`]. It may import `host.add`.
+    pub fn new(
+        module: &[u8],
+        mode: WasmtimeMode,
+        traps: bool,
+        arguments: Arguments,
+    ) -> Result<Self> {
+        let wasm = wasm_binary(module)?;
+        let mut config = Config::new();
+        config.wasm_simd(true);
+        if let WasmtimeMode::Pooling = mode {
+            let mut pooling = PoolingAllocationConfig::default();
+            // Room for the largest memory any workload grows to.
+            pooling.max_memory_size(1 << 32);
+            config.allocation_strategy(InstanceAllocationStrategy::Pooling(pooling));
+            config.memory_init_cow(true);
+        }
+        let engine = Engine::new(&config)?;
+        let module = Module::new(&engine, &wasm)?;
+        let mut linker = Linker::new(&engine);
+        linker.func_wrap("host", "add", |lhs: u32, rhs: u32| lhs.wrapping_add(rhs))?;
+        let pre = linker.instantiate_pre(&module)?;
+        let reuse = mode == WasmtimeMode::Reuse;
+        Ok(Self::from_embedding(
+            Wasmtime { engine, pre },
+            reuse,
+            traps,
+            arguments,
+        ))
+    }
+}
+
+/// wasmtime, with a module compiled and linked ready to instantiate.
+pub struct Wasmtime {
+    engine: Engine,
+    pre: InstancePre<()>,
+}
+
+/// An instance, with its store and the exports the benchmark calls.
+pub struct Instance {
+    store: Store<()>,
+    add: TypedFunc<(u32, u32), u32>,
+    /// `reserve` and the memory, for payload workloads.
+    transfer: Option<(TypedFunc<u32, u32>, Memory)>,
+}
+
+impl Instance {
+    /// The store, `reserve` and the memory, for payload workloads.
+    fn transfer(&mut self) -> (&mut Store<()>, &TypedFunc<u32, u32>, Memory) {
+        let (reserve, memory) = self
+            .transfer
+            .as_ref()
+            .expect("payload workloads export `reserve`");
+        (&mut self.store, reserve, *memory)
+    }
+}
+
+impl Embedding for Wasmtime {
+    type Instance = Instance;
+    type Error = anyhow::Error;
+
+    fn instantiate(&self, payload: bool) -> Result<Instance> {
+        let mut store = Store::new(&self.engine, ());
+        let instance = self.pre.instantiate(&mut store)?;
+        let add = instance.get_typed_func(&mut store, "add")?;
+        let transfer = match payload {
+            true => Some((
+                instance.get_typed_func(&mut store, "reserve")?,
+                instance
+                    .get_memory(&mut store, "memory")
+                    .context("payload workloads must export their memory")?,
+            )),
+            false => None,
+        };
+        Ok(Instance {
+            store,
+            add,
+            transfer,
+        })
+    }
+
+    fn add(instance: &mut Instance, lhs: u32, rhs: u32) -> Result<u32> {
+        instance.add.call(&mut instance.store, (lhs, rhs))
+    }
+
+    fn classify(error: &anyhow::Error) -> Trap {
+        match error.downcast_ref::<wasmtime::Trap>() {
+            Some(wasmtime::Trap::MemoryOutOfBounds) => Trap::OutOfBounds,
+            Some(wasmtime::Trap::UnreachableCodeReached) => Trap::Unreachable,
+            Some(wasmtime::Trap::IntegerDivisionByZero) => Trap::DivideByZero,
+            Some(wasmtime::Trap::StackOverflow) => Trap::StackOverflow,
+            _ => Trap::Other,
+        }
+    }
+
+    fn reserve(instance: &mut Instance, len: u32) -> Result<u32> {
+        let (store, reserve, _) = instance.transfer();
+        reserve.call(store, len)
+    }
+
+    fn write(instance: &mut Instance, offset: usize, bytes: &[u8]) -> Result<()> {
+        let (store, _, memory) = instance.transfer();
+        Ok(memory.write(store, offset, bytes)?)
+    }
+
+    fn read(instance: &mut Instance, offset: usize, bytes: &mut [u8]) -> Result<()> {
+        let (store, _, memory) = instance.transfer();
+        Ok(memory.read(store, offset, bytes)?)
+    }
+}
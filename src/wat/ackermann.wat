@@ -0,0 +1,14 @@
+(module
+  (func $ackermann (param $m i32) (param $n i32) (result i32)
+	(if (i32.eqz (local.get $m))
+	  (then (return (i32.add (local.get $n) (i32.const 1)))))
+	(if (i32.eqz (local.get $n))
+	  (then
+	    (return (call $ackermann (i32.sub (local.get $m) (i32.const 1)) (i32.const 1)))))
+	(call $ackermann
+	  (i32.sub (local.get $m) (i32.const 1))
+	  (call $ackermann (local.get $m) (i32.sub (local.get $n) (i32.const 1)))))
+  ;; Returns A(2, lhs), whose calls nest about 2 * lhs deep.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(call $ackermann (i32.const 2) (local.get $lhs)))
+  (export "add" (func $add)))
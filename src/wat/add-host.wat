@@ -0,0 +1,7 @@
+(module
+  (import "host" "add" (func $host_add (param i32 i32) (result i32)))
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(call $host_add
+	  (local.get $lhs)
+	  (local.get $rhs)))
+  (export "add" (func $add)))
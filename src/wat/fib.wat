@@ -0,0 +1,13 @@
+(module
+  ;; The nth Fibonacci number (wrapping), computed with exponentially many calls.
+  (func $fib (param $n i32) (result i32)
+	(if (result i32) (i32.lt_u (local.get $n) (i32.const 2))
+	  (then (local.get $n))
+	  (else
+	    (i32.add
+	      (call $fib (i32.sub (local.get $n) (i32.const 1)))
+	      (call $fib (i32.sub (local.get $n) (i32.const 2)))))))
+  ;; Returns fib(lhs).
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(call $fib (local.get $lhs)))
+  (export "add" (func $add)))
@@ -0,0 +1,72 @@
+(module
+  ;; Three 64x64 f32 matrices: lhs at 0, rhs at 16 KiB and their product at 32 KiB.
+  (memory $mem 1)
+  ;; As `fmatmul.wat`, computing four elements of the product at once with f32x4.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(local $x i32)
+	(local $y i32)
+	(local $i i32)
+	(local $lhs_row i32)
+	(local $rhs_row i32)
+	(local $sum v128)
+
+	(local.set $i (i32.const 0))
+	(loop $fill
+	      (v128.store (local.get $i) (f32x4.splat (f32.convert_i32_u (local.get $lhs))))
+	      (v128.store offset=16384 (local.get $i) (f32x4.splat (f32.convert_i32_u (local.get $rhs))))
+	      (local.set $i (i32.add (local.get $i) (i32.const 16)))
+	      (i32.lt_u (local.get $i) (i32.const 16384))
+	      br_if $fill)
+
+	(local.set $y (i32.const 0))
+	(loop $matmul_outer
+	      (local.set $lhs_row (i32.shl (local.get $y) (i32.const 8)))
+	      (local.set $x (i32.const 0))
+	      (loop $matmul_inner
+		    (local.set $i (i32.const 0))
+		    (local.set $sum (v128.const i32x4 0 0 0 0))
+		    (loop $matmul_dot
+			  ;; splat(lhs[y][i]) * rhs[i][x..x + 4]
+			  (local.set $rhs_row (i32.shl (local.get $i) (i32.const 8)))
+			  (local.set $sum
+				     (f32x4.add
+				       (local.get $sum)
+				       (f32x4.mul
+					 (f32x4.splat
+					   (f32.load
+					     (i32.add
+					       (local.get $lhs_row)
+					       (i32.shl (local.get $i) (i32.const 2)))))
+					 (v128.load offset=16384
+					   (i32.add
+					     (local.get $rhs_row)
+					     (i32.shl (local.get $x) (i32.const 2)))))))
+			  (local.set $i (i32.add (local.get $i) (i32.const 1)))
+			  (i32.lt_u (local.get $i) (i32.const 64))
+			  br_if $matmul_dot)
+		    (v128.store offset=32768
+		      (i32.add (local.get $lhs_row) (i32.shl (local.get $x) (i32.const 2)))
+		      (local.get $sum))
+		    (local.set $x (i32.add (local.get $x) (i32.const 4)))
+		    (i32.lt_u (local.get $x) (i32.const 64))
+		    br_if $matmul_inner)
+	      (local.set $y (i32.add (local.get $y) (i32.const 1)))
+	      (i32.lt_u (local.get $y) (i32.const 64))
+	      br_if $matmul_outer)
+
+	(local.set $i (i32.const 0))
+	(local.set $sum (v128.const i32x4 0 0 0 0))
+	(loop $total
+	      (local.set $sum (f32x4.add (local.get $sum) (v128.load offset=32768 (local.get $i))))
+	      (local.set $i (i32.add (local.get $i) (i32.const 16)))
+	      (i32.lt_u (local.get $i) (i32.const 16384))
+	      br_if $total)
+	(i32.trunc_f32_u
+	  (f32.add
+	    (f32.add
+	      (f32x4.extract_lane 0 (local.get $sum))
+	      (f32x4.extract_lane 1 (local.get $sum)))
+	    (f32.add
+	      (f32x4.extract_lane 2 (local.get $sum))
+	      (f32x4.extract_lane 3 (local.get $sum))))))
+  (export "add" (func $add)))
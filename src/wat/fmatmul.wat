@@ -0,0 +1,63 @@
+(module
+  ;; Three 64x64 f32 matrices: lhs at 0, rhs at 16 KiB and their product at 32 KiB.
+  (memory $mem 1)
+  ;; Multiplies a matrix filled with lhs by one filled with rhs and returns the sum of the
+  ;; product, truncated to an integer. Every value is an integer below 2^24, so it's exact.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(local $x i32)
+	(local $y i32)
+	(local $i i32)
+	(local $sum f32)
+
+	(local.set $i (i32.const 0))
+	(loop $fill
+	      (f32.store (local.get $i) (f32.convert_i32_u (local.get $lhs)))
+	      (f32.store offset=16384 (local.get $i) (f32.convert_i32_u (local.get $rhs)))
+	      (local.set $i (i32.add (local.get $i) (i32.const 4)))
+	      (i32.lt_u (local.get $i) (i32.const 16384))
+	      br_if $fill)
+
+	(local.set $y (i32.const 0))
+	(loop $matmul_outer
+	      (local.set $x (i32.const 0))
+	      (loop $matmul_inner
+		    (local.set $i (i32.const 0))
+		    (local.set $sum (f32.const 0))
+		    (loop $matmul_dot
+			  ;; lhs[y][i] * rhs[i][x]
+			  (local.set $sum
+				     (f32.add
+				       (local.get $sum)
+				       (f32.mul
+					 (f32.load
+					   (i32.shl
+					     (i32.add (i32.shl (local.get $y) (i32.const 6)) (local.get $i))
+					     (i32.const 2)))
+					 (f32.load offset=16384
+					   (i32.shl
+					     (i32.add (i32.shl (local.get $i) (i32.const 6)) (local.get $x))
+					     (i32.const 2))))))
+			  (local.set $i (i32.add (local.get $i) (i32.const 1)))
+			  (i32.lt_u (local.get $i) (i32.const 64))
+			  br_if $matmul_dot)
+		    (f32.store offset=32768
+		      (i32.shl
+			(i32.add (i32.shl (local.get $y) (i32.const 6)) (local.get $x))
+			(i32.const 2))
+		      (local.get $sum))
+		    (local.set $x (i32.add (local.get $x) (i32.const 1)))
+		    (i32.lt_u (local.get $x) (i32.const 64))
+		    br_if $matmul_inner)
+	      (local.set $y (i32.add (local.get $y) (i32.const 1)))
+	      (i32.lt_u (local.get $y) (i32.const 64))
+	      br_if $matmul_outer)
+
+	(local.set $i (i32.const 0))
+	(local.set $sum (f32.const 0))
+	(loop $total
+	      (local.set $sum (f32.add (local.get $sum) (f32.load offset=32768 (local.get $i))))
+	      (local.set $i (i32.add (local.get $i) (i32.const 4)))
+	      (i32.lt_u (local.get $i) (i32.const 16384))
+	      br_if $total)
+	(i32.trunc_f32_u (local.get $sum)))
+  (export "add" (func $add)))
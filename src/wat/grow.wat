@@ -0,0 +1,20 @@
+(module
+  ;; Up to 1 GiB.
+  (memory $mem 1 16384)
+  ;; Grows memory to lhs MiB, writes to every 4 KiB page of it, and returns lhs + rhs.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(local $size i32)
+	(local $i i32)
+	(local.set $size (i32.shl (local.get $lhs) (i32.const 20)))
+	(if (i32.eq
+	      (memory.grow
+		(i32.sub (i32.shr_u (local.get $size) (i32.const 16)) (memory.size)))
+	      (i32.const -1))
+	  (then (unreachable)))
+	(loop $touch
+	      (i32.store8 (local.get $i) (local.get $lhs))
+	      (local.set $i (i32.add (local.get $i) (i32.const 4096)))
+	      (i32.lt_u (local.get $i) (local.get $size))
+	      br_if $touch)
+	(i32.add (local.get $lhs) (local.get $rhs)))
+  (export "add" (func $add)))
@@ -0,0 +1,14 @@
+(module
+  (import "host" "add" (func $host_add (param i32 i32) (result i32)))
+  ;; Calls the host lhs times, adding rhs each time, and returns lhs * rhs.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(local $acc i32)
+	(local $i i32)
+	(block $done
+	  (loop $call
+		(br_if $done (i32.ge_u (local.get $i) (local.get $lhs)))
+		(local.set $acc (call $host_add (local.get $acc) (local.get $rhs)))
+		(local.set $i (i32.add (local.get $i) (i32.const 1)))
+		(br $call)))
+	(local.get $acc))
+  (export "add" (func $add)))
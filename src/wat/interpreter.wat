@@ -0,0 +1,30 @@
+(module
+  ;; An instruction takes the accumulator and the step number and returns the new accumulator.
+  (type $instruction (func (param i32 i32) (result i32)))
+  (table 4 funcref)
+  (elem (i32.const 0) $plus $triple $xor $halve)
+  (func $plus (type $instruction)
+	(i32.add (local.get 0) (local.get 1)))
+  (func $triple (type $instruction)
+	(i32.mul (local.get 0) (i32.const 3)))
+  (func $xor (type $instruction)
+	(i32.xor (local.get 0) (local.get 1)))
+  (func $halve (type $instruction)
+	(i32.shr_u (local.get 0) (i32.const 1)))
+  ;; Runs lhs steps, each calling the instruction picked by the accumulator and step number
+  ;; through the table, and returns the accumulator.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(local $acc i32)
+	(local $i i32)
+	(block $done
+	  (loop $step
+		(br_if $done (i32.ge_u (local.get $i) (local.get $lhs)))
+		(local.set $acc
+		  (call_indirect (type $instruction)
+		    (local.get $acc)
+		    (local.get $i)
+		    (i32.and (i32.xor (local.get $acc) (local.get $i)) (i32.const 3))))
+		(local.set $i (i32.add (local.get $i) (i32.const 1)))
+		(br $step)))
+	(local.get $acc))
+  (export "add" (func $add)))
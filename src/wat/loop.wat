@@ -0,0 +1,6 @@
+(module
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(loop $forever
+	      br $forever)
+	unreachable)
+  (export "add" (func $add)))
@@ -1,7 +1,9 @@
+;; A template for `benchmark::matmul::wat`, which fills in the dimension and the pages of memory
+;; its three matrices need.
 (module
-  (global $dim i32 (i32.const 128))
+  (global $dim i32 (i32.const {{DIM}}))
   (global $size (mut i32) (i32.const 0))
-  (memory $mem 3) ;; >= ($dim**2)*4*3/65536
+  (memory $mem {{PAGES}})
 
   (func $set (param $idx i32) (param $x i32) (param $y i32) (param $val i32)
 	(local $base i32)
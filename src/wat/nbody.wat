@@ -0,0 +1,166 @@
+(module
+  ;; The five bodies of the benchmarks game n-body simulation, 64 bytes each: the position at 0,
+  ;; the velocity at 32 and the mass at 56, as f64s.
+  (memory $mem 1)
+
+  ;; The sun and the outer planets, with the sun's velocity offsetting their momentum.
+  (func $init
+	;; Sun
+	(f64.store (i32.const 0) (f64.const 0.0))
+	(f64.store offset=8 (i32.const 0) (f64.const 0.0))
+	(f64.store offset=16 (i32.const 0) (f64.const 0.0))
+	(f64.store offset=32 (i32.const 0) (f64.const -0.00038766340719874267))
+	(f64.store offset=40 (i32.const 0) (f64.const -0.0032753590371765707))
+	(f64.store offset=48 (i32.const 0) (f64.const 2.3935734080003e-05))
+	(f64.store offset=56 (i32.const 0) (f64.const 39.47841760435743))
+	;; Jupiter
+	(f64.store (i32.const 64) (f64.const 4.841431442464721))
+	(f64.store offset=8 (i32.const 64) (f64.const -1.1603200440274284))
+	(f64.store offset=16 (i32.const 64) (f64.const -0.10362204447112311))
+	(f64.store offset=32 (i32.const 64) (f64.const 0.606326392995832))
+	(f64.store offset=40 (i32.const 64) (f64.const 2.81198684491626))
+	(f64.store offset=48 (i32.const 64) (f64.const -0.02521836165988763))
+	(f64.store offset=56 (i32.const 64) (f64.const 0.03769367487038949))
+	;; Saturn
+	(f64.store (i32.const 128) (f64.const 8.34336671824458))
+	(f64.store offset=8 (i32.const 128) (f64.const 4.124798564124305))
+	(f64.store offset=16 (i32.const 128) (f64.const -0.4035234171143214))
+	(f64.store offset=32 (i32.const 128) (f64.const -1.0107743461787924))
+	(f64.store offset=40 (i32.const 128) (f64.const 1.8256623712304119))
+	(f64.store offset=48 (i32.const 128) (f64.const 0.008415761376584154))
+	(f64.store offset=56 (i32.const 128) (f64.const 0.011286326131968767))
+	;; Uranus
+	(f64.store (i32.const 192) (f64.const 12.894369562139131))
+	(f64.store offset=8 (i32.const 192) (f64.const -15.111151401698631))
+	(f64.store offset=16 (i32.const 192) (f64.const -0.22330757889265573))
+	(f64.store offset=32 (i32.const 192) (f64.const 1.0827910064415354))
+	(f64.store offset=40 (i32.const 192) (f64.const 0.8687130181696082))
+	(f64.store offset=48 (i32.const 192) (f64.const -0.010832637401363636))
+	(f64.store offset=56 (i32.const 192) (f64.const 0.0017237240570597112))
+	;; Neptune
+	(f64.store (i32.const 256) (f64.const 15.379697114850917))
+	(f64.store offset=8 (i32.const 256) (f64.const -25.919314609987964))
+	(f64.store offset=16 (i32.const 256) (f64.const 0.17925877295037118))
+	(f64.store offset=32 (i32.const 256) (f64.const 0.979090732243898))
+	(f64.store offset=40 (i32.const 256) (f64.const 0.5946989986476762))
+	(f64.store offset=48 (i32.const 256) (f64.const -0.034755955504078104))
+	(f64.store offset=56 (i32.const 256) (f64.const 0.0020336868699246304)))
+
+  ;; Advances the simulation by a 0.01 step.
+  (func $advance
+	(local $i i32)
+	(local $j i32)
+	(local $dx f64)
+	(local $dy f64)
+	(local $dz f64)
+	(local $d2 f64)
+	(local $mag f64)
+	(local $mi f64)
+	(local $mj f64)
+
+	(local.set $i (i32.const 0))
+	(loop $outer
+	      (local.set $j (i32.add (local.get $i) (i32.const 64)))
+	      (block $done
+		(loop $inner
+		      (br_if $done (i32.ge_u (local.get $j) (i32.const 320)))
+		      (local.set $dx (f64.sub (f64.load (local.get $i)) (f64.load (local.get $j))))
+		      (local.set $dy (f64.sub (f64.load offset=8 (local.get $i)) (f64.load offset=8 (local.get $j))))
+		      (local.set $dz (f64.sub (f64.load offset=16 (local.get $i)) (f64.load offset=16 (local.get $j))))
+		      (local.set $d2
+				 (f64.add
+				   (f64.add
+				     (f64.mul (local.get $dx) (local.get $dx))
+				     (f64.mul (local.get $dy) (local.get $dy)))
+				   (f64.mul (local.get $dz) (local.get $dz))))
+		      (local.set $mag
+				 (f64.div
+				   (f64.const 0.01)
+				   (f64.mul (local.get $d2) (f64.sqrt (local.get $d2)))))
+		      (local.set $mi (f64.mul (f64.load offset=56 (local.get $i)) (local.get $mag)))
+		      (local.set $mj (f64.mul (f64.load offset=56 (local.get $j)) (local.get $mag)))
+		      (f64.store offset=32 (local.get $i)
+				 (f64.sub (f64.load offset=32 (local.get $i)) (f64.mul (local.get $dx) (local.get $mj))))
+		      (f64.store offset=40 (local.get $i)
+				 (f64.sub (f64.load offset=40 (local.get $i)) (f64.mul (local.get $dy) (local.get $mj))))
+		      (f64.store offset=48 (local.get $i)
+				 (f64.sub (f64.load offset=48 (local.get $i)) (f64.mul (local.get $dz) (local.get $mj))))
+		      (f64.store offset=32 (local.get $j)
+				 (f64.add (f64.load offset=32 (local.get $j)) (f64.mul (local.get $dx) (local.get $mi))))
+		      (f64.store offset=40 (local.get $j)
+				 (f64.add (f64.load offset=40 (local.get $j)) (f64.mul (local.get $dy) (local.get $mi))))
+		      (f64.store offset=48 (local.get $j)
+				 (f64.add (f64.load offset=48 (local.get $j)) (f64.mul (local.get $dz) (local.get $mi))))
+		      (local.set $j (i32.add (local.get $j) (i32.const 64)))
+		      (br $inner)))
+	      (local.set $i (i32.add (local.get $i) (i32.const 64)))
+	      (br_if $outer (i32.lt_u (local.get $i) (i32.const 320))))
+
+	(local.set $i (i32.const 0))
+	(loop $move
+	      (f64.store (local.get $i)
+			 (f64.add (f64.load (local.get $i)) (f64.mul (f64.const 0.01) (f64.load offset=32 (local.get $i)))))
+	      (f64.store offset=8 (local.get $i)
+			 (f64.add (f64.load offset=8 (local.get $i)) (f64.mul (f64.const 0.01) (f64.load offset=40 (local.get $i)))))
+	      (f64.store offset=16 (local.get $i)
+			 (f64.add (f64.load offset=16 (local.get $i)) (f64.mul (f64.const 0.01) (f64.load offset=48 (local.get $i)))))
+	      (local.set $i (i32.add (local.get $i) (i32.const 64)))
+	      (br_if $move (i32.lt_u (local.get $i) (i32.const 320)))))
+
+  ;; The total kinetic and potential energy of the bodies.
+  (func $energy (result f64)
+	(local $i i32)
+	(local $j i32)
+	(local $e f64)
+	(local $dx f64)
+	(local $dy f64)
+	(local $dz f64)
+
+	(local.set $i (i32.const 0))
+	(loop $outer
+	      (local.set $e
+			 (f64.add
+			   (local.get $e)
+			   (f64.mul
+			     (f64.mul (f64.const 0.5) (f64.load offset=56 (local.get $i)))
+			     (f64.add
+			       (f64.add
+				 (f64.mul (f64.load offset=32 (local.get $i)) (f64.load offset=32 (local.get $i)))
+				 (f64.mul (f64.load offset=40 (local.get $i)) (f64.load offset=40 (local.get $i))))
+			       (f64.mul (f64.load offset=48 (local.get $i)) (f64.load offset=48 (local.get $i)))))))
+	      (local.set $j (i32.add (local.get $i) (i32.const 64)))
+	      (block $done
+		(loop $inner
+		      (br_if $done (i32.ge_u (local.get $j) (i32.const 320)))
+		      (local.set $dx (f64.sub (f64.load (local.get $i)) (f64.load (local.get $j))))
+		      (local.set $dy (f64.sub (f64.load offset=8 (local.get $i)) (f64.load offset=8 (local.get $j))))
+		      (local.set $dz (f64.sub (f64.load offset=16 (local.get $i)) (f64.load offset=16 (local.get $j))))
+		      (local.set $e
+				 (f64.sub
+				   (local.get $e)
+				   (f64.div
+				     (f64.mul (f64.load offset=56 (local.get $i)) (f64.load offset=56 (local.get $j)))
+				     (f64.sqrt
+				       (f64.add
+					 (f64.add
+					   (f64.mul (local.get $dx) (local.get $dx))
+					   (f64.mul (local.get $dy) (local.get $dy)))
+					 (f64.mul (local.get $dz) (local.get $dz)))))))
+		      (local.set $j (i32.add (local.get $j) (i32.const 64)))
+		      (br $inner)))
+	      (local.set $i (i32.add (local.get $i) (i32.const 64)))
+	      (br_if $outer (i32.lt_u (local.get $i) (i32.const 320))))
+	(local.get $e))
+
+  ;; Runs the simulation for lhs steps and returns the energy in nanojoules, truncated.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(local $step i32)
+	(call $init)
+	(block $done
+	  (loop $steps
+		(br_if $done (i32.ge_u (local.get $step) (local.get $lhs)))
+		(call $advance)
+		(local.set $step (i32.add (local.get $step) (i32.const 1)))
+		(br $steps)))
+	(i32.trunc_f64_s (f64.mul (call $energy) (f64.const 1e9))))
+  (export "add" (func $add)))
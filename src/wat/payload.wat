@@ -0,0 +1,37 @@
+(module
+  ;; Up to 1 GiB, for an input and output of up to 512 MiB each.
+  (memory $mem 1 16384)
+  ;; Grows memory to fit a len-byte input followed by its output, and returns the input's offset.
+  (func $reserve (param $len i32) (result i32)
+	(local $pages i32)
+	(local.set $pages
+	  (i32.sub
+	    (i32.shr_u
+	      (i32.add (i32.shl (local.get $len) (i32.const 1)) (i32.const 65535))
+	      (i32.const 16))
+	    (memory.size)))
+	(if (i32.gt_s (local.get $pages) (i32.const 0))
+	  (then
+	    (if (i32.eq (memory.grow (local.get $pages)) (i32.const -1))
+	      (then (unreachable)))))
+	(i32.const 0))
+  ;; Writes the len-byte input at offset input, with every byte inverted, just after it, and
+  ;; returns the output's offset.
+  (func $add (param $len i32) (param $input i32) (result i32)
+	(local $output i32)
+	(local $i i32)
+	(local.set $output (i32.add (local.get $input) (local.get $len)))
+	(block $done
+	  (loop $invert
+		(br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+		(i32.store8
+		  (i32.add (local.get $output) (local.get $i))
+		  (i32.xor
+		    (i32.load8_u (i32.add (local.get $input) (local.get $i)))
+		    (i32.const 255)))
+		(local.set $i (i32.add (local.get $i) (i32.const 1)))
+		(br $invert)))
+	(local.get $output))
+  (export "memory" (memory $mem))
+  (export "reserve" (func $reserve))
+  (export "add" (func $add)))
@@ -0,0 +1,9 @@
+(module
+  ;; Divides rhs by zero unless lhs is 0, in which case it returns rhs.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(if (i32.eqz (local.get $lhs))
+	  (then (return (local.get $rhs))))
+	(i32.div_u
+	  (local.get $rhs)
+	  (i32.sub (local.get $lhs) (local.get $lhs))))
+  (export "add" (func $add)))
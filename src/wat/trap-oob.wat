@@ -0,0 +1,8 @@
+(module
+  (memory $mem 1)
+  ;; Loads past the end of memory unless lhs is 0, in which case it returns rhs.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(if (i32.eqz (local.get $lhs))
+	  (then (return (local.get $rhs))))
+	(i32.load (i32.const 0x7ffffff0)))
+  (export "add" (func $add)))
@@ -0,0 +1,11 @@
+(module
+  (func $recurse (param $depth i32) (result i32)
+	(i32.add
+	  (call $recurse (i32.add (local.get $depth) (i32.const 1)))
+	  (i32.const 1)))
+  ;; Recurses until the stack overflows unless lhs is 0, in which case it returns rhs.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(if (i32.eqz (local.get $lhs))
+	  (then (return (local.get $rhs))))
+	(call $recurse (i32.const 0)))
+  (export "add" (func $add)))
@@ -0,0 +1,7 @@
+(module
+  ;; Executes `unreachable` unless lhs is 0, in which case it returns rhs.
+  (func $add (param $lhs i32) (param $rhs i32) (result i32)
+	(if (i32.eqz (local.get $lhs))
+	  (then (return (local.get $rhs))))
+	(unreachable))
+  (export "add" (func $add)))
@@ -0,0 +1,97 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+use core::arch::asm;
+
+const DIM: usize = 64;
+
+/// Three 64x64 f32 matrices: lhs, rhs and their product.
+static mut MEMORY: [f32; 3 * DIM * DIM] = [0.0; 3 * DIM * DIM];
+
+// The target is soft-float, so Rust's floating-point arithmetic would call into
+// `compiler_builtins`. The arithmetic is in assembly instead, to use scalar SSE like the wasm
+// runtimes do.
+
+/// Store the dot product of the row of lhs at `row` and the column of rhs at `column` to `out`.
+#[target_feature(enable = "sse2")]
+unsafe fn dot(row: *const f32, column: *const f32, out: *mut f32) {
+    unsafe {
+        asm!(
+            "xorps xmm0, xmm0",
+            "2:",
+            "movss xmm1, [{row}]",
+            "mulss xmm1, [{column}]",
+            "addss xmm0, xmm1",
+            "add {row}, 4",
+            "add {column}, {stride}",
+            "dec {n}",
+            "jnz 2b",
+            "movss [{out}], xmm0",
+            row = inout(reg) row => _,
+            column = inout(reg) column => _,
+            n = inout(reg) DIM => _,
+            stride = const 4 * DIM,
+            out = in(reg) out,
+            out("xmm0") _,
+            out("xmm1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// The sum of the `len` values at `values`, truncated to an integer.
+#[target_feature(enable = "sse2")]
+unsafe fn sum(values: *const f32, len: usize) -> u32 {
+    let sum: u64;
+    unsafe {
+        asm!(
+            "xorps xmm0, xmm0",
+            "2:",
+            "addss xmm0, [{values}]",
+            "add {values}, 4",
+            "dec {n}",
+            "jnz 2b",
+            "cvttss2si {sum}, xmm0",
+            values = inout(reg) values => _,
+            n = inout(reg) len => _,
+            sum = out(reg) sum,
+            out("xmm0") _,
+            options(nostack),
+        );
+    }
+    sum as u32
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut lhs: u64 = 0;
+        let mut rhs: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut lhs);
+        user::syscall::read_word_unchecked(1, &mut rhs);
+
+        let memory = &mut *&raw mut MEMORY;
+        let (a, rest) = memory.split_at_mut(DIM * DIM);
+        let (b, c) = rest.split_at_mut(DIM * DIM);
+        a.fill(lhs as u32 as f32);
+        b.fill(rhs as u32 as f32);
+
+        for y in 0..DIM {
+            for x in 0..DIM {
+                dot(a[DIM * y..].as_ptr(), b[x..].as_ptr(), &mut c[DIM * y + x]);
+            }
+        }
+
+        let z = sum(c.as_ptr(), c.len());
+
+        user::syscall::create_word(0, z as u64);
+        user::syscall::exit(0);
+    }
+}
@@ -0,0 +1,43 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+/// How much memory the workload has, which the benchmark patches into the binary along with the
+/// memory itself (see `benchmark::arca::reserve_memory`).
+#[unsafe(no_mangle)]
+static MEMORY_SIZE: usize = 0;
+
+unsafe extern "C" {
+    /// The end of the binary's segments, after which the memory starts at the next page.
+    static mut _end: u8;
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        // Write to every 4 KiB page of the first x MiB, as the wasm workload does after growing its
+        // memory to that size.
+        let size = (x as usize) << 20;
+        assert!(size <= core::ptr::read_volatile(&raw const MEMORY_SIZE));
+        let end = &raw mut _end;
+        let memory = end.with_addr(end.addr().next_multiple_of(4096));
+        for i in (0..size).step_by(4096) {
+            memory.add(i).write_volatile(x as u8);
+        }
+        let z = x as u32 + y as u32;
+
+        user::syscall::create_word(0, z as u64);
+        user::syscall::exit(0);
+    }
+}
@@ -0,0 +1,88 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+/// The dimension of the matrices, which the benchmark patches into the binary (see
+/// `benchmark::arca::patch_static`).
+#[unsafe(no_mangle)]
+static MATMUL_DIM: usize = 64;
+
+/// How much memory the workload has, which the benchmark patches into the binary along with the
+/// memory itself (see `benchmark::arca::reserve_memory`).
+#[unsafe(no_mangle)]
+static MEMORY_SIZE: usize = 0;
+
+unsafe extern "C" {
+    /// The end of the binary's segments, after which the memory starts at the next page.
+    static mut _end: u8;
+}
+
+/// The three matrices, one after another.
+fn memory() -> *mut u32 {
+    let end = &raw mut _end;
+    end.with_addr(end.addr().next_multiple_of(4096)).cast()
+}
+
+unsafe fn set(dim: usize, idx: usize, x: usize, y: usize, val: u32) {
+    unsafe {
+        memory().add(dim * dim * idx + dim * y + x).write(val);
+    }
+}
+
+unsafe fn get(dim: usize, idx: usize, x: usize, y: usize) -> u32 {
+    unsafe { memory().add(dim * dim * idx + dim * y + x).read() }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        // Read the dimension as it is in the binary, rather than as it was compiled.
+        let dim = core::ptr::read_volatile(&raw const MATMUL_DIM);
+        assert!(3 * dim * dim * 4 <= core::ptr::read_volatile(&raw const MEMORY_SIZE));
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut lhs: u64 = 0;
+        let mut rhs: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut lhs);
+        user::syscall::read_word_unchecked(1, &mut rhs);
+
+        let lhs = lhs as u32;
+        let rhs = rhs as u32;
+
+        for y in 0..dim {
+            for x in 0..dim {
+                set(dim, 0, x, y, lhs);
+            }
+        }
+
+        for y in 0..dim {
+            for x in 0..dim {
+                set(dim, 1, x, y, rhs);
+            }
+        }
+
+        for y in 0..dim {
+            for x in 0..dim {
+                let mut sum: u32 = 0;
+                for i in 0..dim {
+                    sum = sum.wrapping_add(get(dim, 0, i, y).wrapping_mul(get(dim, 1, x, i)));
+                }
+                set(dim, 2, x, y, sum);
+            }
+        }
+
+        let mut sum: u32 = 0;
+        for y in 0..dim {
+            for x in 0..dim {
+                sum = sum.wrapping_add(get(dim, 2, x, y));
+            }
+        }
+
+        user::syscall::create_word(0, sum as u64);
+        user::syscall::exit(0);
+    }
+}
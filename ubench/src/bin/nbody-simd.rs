@@ -0,0 +1,226 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+use core::arch::asm;
+use kernels::nbody::{BODIES, DT};
+
+// As `nbody.rs`, with the x and y components of positions and velocities in one packed SSE
+// register and the z component on its own.
+
+/// Apply the gravity between bodies `i` and `j` to their velocities for a step.
+#[target_feature(enable = "sse2")]
+unsafe fn interact(i: *mut [f64; 8], j: *mut [f64; 8]) {
+    unsafe {
+        asm!(
+            // dxy = i.position.xy - j.position.xy, dz = i.position.z - j.position.z
+            "movupd xmm0, [{i}]",
+            "movupd xmm1, [{j}]",
+            "subpd xmm0, xmm1",
+            "movsd xmm2, [{i} + 16]",
+            "subsd xmm2, [{j} + 16]",
+            // d2 = dx * dx + dy * dy + dz * dz
+            "movapd xmm3, xmm0",
+            "mulpd xmm3, xmm0",
+            "movapd xmm4, xmm3",
+            "unpckhpd xmm4, xmm4",
+            "addsd xmm3, xmm4",
+            "movapd xmm4, xmm2",
+            "mulsd xmm4, xmm2",
+            "addsd xmm3, xmm4",
+            // mag = DT / (d2 * sqrt(d2))
+            "sqrtsd xmm4, xmm3",
+            "mulsd xmm4, xmm3",
+            "movsd xmm5, [{dt}]",
+            "divsd xmm5, xmm4",
+            // mi = i.mass * mag, mj = j.mass * mag, in both lanes
+            "movsd xmm6, [{i} + 56]",
+            "mulsd xmm6, xmm5",
+            "unpcklpd xmm6, xmm6",
+            "movsd xmm7, [{j} + 56]",
+            "mulsd xmm7, xmm5",
+            "unpcklpd xmm7, xmm7",
+            // i.velocity -= d * mj
+            "movapd xmm4, xmm0",
+            "mulpd xmm4, xmm7",
+            "movupd xmm5, [{i} + 32]",
+            "subpd xmm5, xmm4",
+            "movupd [{i} + 32], xmm5",
+            "movapd xmm4, xmm2",
+            "mulsd xmm4, xmm7",
+            "movsd xmm5, [{i} + 48]",
+            "subsd xmm5, xmm4",
+            "movsd [{i} + 48], xmm5",
+            // j.velocity += d * mi
+            "mulpd xmm0, xmm6",
+            "movupd xmm5, [{j} + 32]",
+            "addpd xmm5, xmm0",
+            "movupd [{j} + 32], xmm5",
+            "mulsd xmm2, xmm6",
+            "addsd xmm2, [{j} + 48]",
+            "movsd [{j} + 48], xmm2",
+            i = in(reg) i,
+            j = in(reg) j,
+            dt = in(reg) &DT,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Move `body` by its velocity for a step.
+#[target_feature(enable = "sse2")]
+unsafe fn movement(body: *mut [f64; 8]) {
+    unsafe {
+        asm!(
+            // position += DT * velocity
+            "movsd xmm0, [{dt}]",
+            "unpcklpd xmm0, xmm0",
+            "movupd xmm1, [{body} + 32]",
+            "mulpd xmm1, xmm0",
+            "movupd xmm2, [{body}]",
+            "addpd xmm2, xmm1",
+            "movupd [{body}], xmm2",
+            "mulsd xmm0, [{body} + 48]",
+            "addsd xmm0, [{body} + 16]",
+            "movsd [{body} + 16], xmm0",
+            body = in(reg) body,
+            dt = in(reg) &DT,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Add the kinetic energy of `body` to the energy at `e`.
+#[target_feature(enable = "sse2")]
+unsafe fn kinetic(body: *const [f64; 8], e: *mut f64) {
+    unsafe {
+        asm!(
+            // e += 0.5 * mass * (vx * vx + vy * vy + vz * vz)
+            "movupd xmm0, [{body} + 32]",
+            "mulpd xmm0, xmm0",
+            "movapd xmm1, xmm0",
+            "unpckhpd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "movsd xmm1, [{body} + 48]",
+            "mulsd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "movsd xmm1, [{half}]",
+            "mulsd xmm1, [{body} + 56]",
+            "mulsd xmm0, xmm1",
+            "addsd xmm0, [{e}]",
+            "movsd [{e}], xmm0",
+            body = in(reg) body,
+            e = in(reg) e,
+            half = in(reg) &0.5f64,
+            out("xmm0") _,
+            out("xmm1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Subtract the potential energy between bodies `i` and `j` from the energy at `e`.
+#[target_feature(enable = "sse2")]
+unsafe fn potential(i: *const [f64; 8], j: *const [f64; 8], e: *mut f64) {
+    unsafe {
+        asm!(
+            // e -= i.mass * j.mass / sqrt(dx * dx + dy * dy + dz * dz)
+            "movupd xmm0, [{i}]",
+            "movupd xmm1, [{j}]",
+            "subpd xmm0, xmm1",
+            "mulpd xmm0, xmm0",
+            "movapd xmm1, xmm0",
+            "unpckhpd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "movsd xmm1, [{i} + 16]",
+            "subsd xmm1, [{j} + 16]",
+            "mulsd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "sqrtsd xmm0, xmm0",
+            "movsd xmm1, [{i} + 56]",
+            "mulsd xmm1, [{j} + 56]",
+            "divsd xmm1, xmm0",
+            "movsd xmm0, [{e}]",
+            "subsd xmm0, xmm1",
+            "movsd [{e}], xmm0",
+            i = in(reg) i,
+            j = in(reg) j,
+            e = in(reg) e,
+            out("xmm0") _,
+            out("xmm1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// The energy in nanojoules, truncated, after `steps` steps of the simulation.
+#[target_feature(enable = "sse2")]
+unsafe fn simulate(steps: u32) -> u32 {
+    let mut bodies = BODIES;
+    let bodies = bodies.as_mut_ptr();
+    unsafe {
+        for _ in 0..steps {
+            for i in 0..5 {
+                for j in i + 1..5 {
+                    interact(bodies.add(i), bodies.add(j));
+                }
+            }
+            for i in 0..5 {
+                movement(bodies.add(i));
+            }
+        }
+
+        let mut e = 0.0f64;
+        for i in 0..5 {
+            kinetic(bodies.add(i), &mut e);
+            for j in i + 1..5 {
+                potential(bodies.add(i), bodies.add(j), &mut e);
+            }
+        }
+
+        let nanojoules: i32;
+        asm!(
+            "movsd xmm0, [{e}]",
+            "mulsd xmm0, [{nano}]",
+            "cvttsd2si {nanojoules:e}, xmm0",
+            e = in(reg) &e,
+            nano = in(reg) &1e9f64,
+            nanojoules = out(reg) nanojoules,
+            out("xmm0") _,
+            options(nostack),
+        );
+        nanojoules as u32
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        let z = simulate(x as u32);
+
+        user::syscall::create_word(0, z as u64);
+        user::syscall::exit(0);
+    }
+}
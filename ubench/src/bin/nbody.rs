@@ -0,0 +1,233 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+use core::arch::asm;
+use kernels::nbody::{BODIES, DT};
+
+// The target is soft-float, so Rust's floating-point arithmetic would call into
+// `compiler_builtins`. The arithmetic is in assembly instead, to use scalar SSE like the wasm
+// runtimes do, in the same order as the other builds.
+
+/// Apply the gravity between bodies `i` and `j` to their velocities for a step.
+#[target_feature(enable = "sse2")]
+unsafe fn interact(i: *mut [f64; 8], j: *mut [f64; 8]) {
+    unsafe {
+        asm!(
+            // d = i.position - j.position
+            "movsd xmm0, [{i}]",
+            "subsd xmm0, [{j}]",
+            "movsd xmm1, [{i} + 8]",
+            "subsd xmm1, [{j} + 8]",
+            "movsd xmm2, [{i} + 16]",
+            "subsd xmm2, [{j} + 16]",
+            // d2 = dx * dx + dy * dy + dz * dz
+            "movapd xmm3, xmm0",
+            "mulsd xmm3, xmm0",
+            "movapd xmm4, xmm1",
+            "mulsd xmm4, xmm1",
+            "addsd xmm3, xmm4",
+            "movapd xmm4, xmm2",
+            "mulsd xmm4, xmm2",
+            "addsd xmm3, xmm4",
+            // mag = DT / (d2 * sqrt(d2))
+            "sqrtsd xmm4, xmm3",
+            "mulsd xmm4, xmm3",
+            "movsd xmm5, [{dt}]",
+            "divsd xmm5, xmm4",
+            // mi = i.mass * mag, mj = j.mass * mag
+            "movsd xmm6, [{i} + 56]",
+            "mulsd xmm6, xmm5",
+            "movsd xmm7, [{j} + 56]",
+            "mulsd xmm7, xmm5",
+            // i.velocity -= d * mj
+            "movapd xmm4, xmm0",
+            "mulsd xmm4, xmm7",
+            "movsd xmm5, [{i} + 32]",
+            "subsd xmm5, xmm4",
+            "movsd [{i} + 32], xmm5",
+            "movapd xmm4, xmm1",
+            "mulsd xmm4, xmm7",
+            "movsd xmm5, [{i} + 40]",
+            "subsd xmm5, xmm4",
+            "movsd [{i} + 40], xmm5",
+            "movapd xmm4, xmm2",
+            "mulsd xmm4, xmm7",
+            "movsd xmm5, [{i} + 48]",
+            "subsd xmm5, xmm4",
+            "movsd [{i} + 48], xmm5",
+            // j.velocity += d * mi
+            "mulsd xmm0, xmm6",
+            "addsd xmm0, [{j} + 32]",
+            "movsd [{j} + 32], xmm0",
+            "mulsd xmm1, xmm6",
+            "addsd xmm1, [{j} + 40]",
+            "movsd [{j} + 40], xmm1",
+            "mulsd xmm2, xmm6",
+            "addsd xmm2, [{j} + 48]",
+            "movsd [{j} + 48], xmm2",
+            i = in(reg) i,
+            j = in(reg) j,
+            dt = in(reg) &DT,
+            out("xmm0") _,
+            out("xmm1") _,
+            out("xmm2") _,
+            out("xmm3") _,
+            out("xmm4") _,
+            out("xmm5") _,
+            out("xmm6") _,
+            out("xmm7") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Move `body` by its velocity for a step.
+#[target_feature(enable = "sse2")]
+unsafe fn movement(body: *mut [f64; 8]) {
+    unsafe {
+        asm!(
+            // position += DT * velocity
+            "movsd xmm0, [{dt}]",
+            "mulsd xmm0, [{body} + 32]",
+            "addsd xmm0, [{body}]",
+            "movsd [{body}], xmm0",
+            "movsd xmm0, [{dt}]",
+            "mulsd xmm0, [{body} + 40]",
+            "addsd xmm0, [{body} + 8]",
+            "movsd [{body} + 8], xmm0",
+            "movsd xmm0, [{dt}]",
+            "mulsd xmm0, [{body} + 48]",
+            "addsd xmm0, [{body} + 16]",
+            "movsd [{body} + 16], xmm0",
+            body = in(reg) body,
+            dt = in(reg) &DT,
+            out("xmm0") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Add the kinetic energy of `body` to the energy at `e`.
+#[target_feature(enable = "sse2")]
+unsafe fn kinetic(body: *const [f64; 8], e: *mut f64) {
+    unsafe {
+        asm!(
+            // e += 0.5 * mass * (vx * vx + vy * vy + vz * vz)
+            "movsd xmm0, [{body} + 32]",
+            "mulsd xmm0, xmm0",
+            "movsd xmm1, [{body} + 40]",
+            "mulsd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "movsd xmm1, [{body} + 48]",
+            "mulsd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "movsd xmm1, [{half}]",
+            "mulsd xmm1, [{body} + 56]",
+            "mulsd xmm0, xmm1",
+            "addsd xmm0, [{e}]",
+            "movsd [{e}], xmm0",
+            body = in(reg) body,
+            e = in(reg) e,
+            half = in(reg) &0.5f64,
+            out("xmm0") _,
+            out("xmm1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// Subtract the potential energy between bodies `i` and `j` from the energy at `e`.
+#[target_feature(enable = "sse2")]
+unsafe fn potential(i: *const [f64; 8], j: *const [f64; 8], e: *mut f64) {
+    unsafe {
+        asm!(
+            // e -= i.mass * j.mass / sqrt(dx * dx + dy * dy + dz * dz)
+            "movsd xmm0, [{i}]",
+            "subsd xmm0, [{j}]",
+            "mulsd xmm0, xmm0",
+            "movsd xmm1, [{i} + 8]",
+            "subsd xmm1, [{j} + 8]",
+            "mulsd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "movsd xmm1, [{i} + 16]",
+            "subsd xmm1, [{j} + 16]",
+            "mulsd xmm1, xmm1",
+            "addsd xmm0, xmm1",
+            "sqrtsd xmm0, xmm0",
+            "movsd xmm1, [{i} + 56]",
+            "mulsd xmm1, [{j} + 56]",
+            "divsd xmm1, xmm0",
+            "movsd xmm0, [{e}]",
+            "subsd xmm0, xmm1",
+            "movsd [{e}], xmm0",
+            i = in(reg) i,
+            j = in(reg) j,
+            e = in(reg) e,
+            out("xmm0") _,
+            out("xmm1") _,
+            options(nostack),
+        );
+    }
+}
+
+/// The energy in nanojoules, truncated, after `steps` steps of the simulation.
+#[target_feature(enable = "sse2")]
+unsafe fn simulate(steps: u32) -> u32 {
+    let mut bodies = BODIES;
+    let bodies = bodies.as_mut_ptr();
+    unsafe {
+        for _ in 0..steps {
+            for i in 0..5 {
+                for j in i + 1..5 {
+                    interact(bodies.add(i), bodies.add(j));
+                }
+            }
+            for i in 0..5 {
+                movement(bodies.add(i));
+            }
+        }
+
+        let mut e = 0.0f64;
+        for i in 0..5 {
+            kinetic(bodies.add(i), &mut e);
+            for j in i + 1..5 {
+                potential(bodies.add(i), bodies.add(j), &mut e);
+            }
+        }
+
+        let nanojoules: i32;
+        asm!(
+            "movsd xmm0, [{e}]",
+            "mulsd xmm0, [{nano}]",
+            "cvttsd2si {nanojoules:e}, xmm0",
+            e = in(reg) &e,
+            nano = in(reg) &1e9f64,
+            nanojoules = out(reg) nanojoules,
+            out("xmm0") _,
+            options(nostack),
+        );
+        nanojoules as u32
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        let z = simulate(x as u32);
+
+        user::syscall::create_word(0, z as u64);
+        user::syscall::exit(0);
+    }
+}
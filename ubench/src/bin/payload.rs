@@ -0,0 +1,40 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+/// How much memory the workload has, which the benchmark patches into the binary along with the
+/// memory itself (see `benchmark::arca::reserve_memory`).
+#[unsafe(no_mangle)]
+static MEMORY_SIZE: usize = 0;
+
+unsafe extern "C" {
+    /// The end of the binary's segments, after which the memory starts at the next page.
+    static mut _end: u8;
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut len: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut len);
+        let len = len as usize;
+        // The input, followed by the output.
+        assert!(2 * len <= core::ptr::read_volatile(&raw const MEMORY_SIZE));
+        let end = &raw mut _end;
+        let memory = end.with_addr(end.addr().next_multiple_of(4096));
+        let input = core::slice::from_raw_parts_mut(memory, len);
+        let output = core::slice::from_raw_parts_mut(memory.add(len), len);
+        user::syscall::read_blob_unchecked(1, input);
+
+        kernels::payload::transform(input, output);
+
+        user::syscall::create_blob(0, output);
+        user::syscall::exit(0);
+    }
+}
@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+static mut SCRATCH: [u8; kernels::SCRATCH_SIZE] = [0; kernels::SCRATCH_SIZE];
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        let z = kernels::thumbnail::run(x as u32, &mut *&raw mut SCRATCH);
+
+        user::syscall::create_word(0, z as u64);
+        user::syscall::exit(0);
+    }
+}
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        // Divide y by zero unless x is 0, in which case return y. This uses `div` directly, since
+        // Rust's division would check for zero and panic instead of faulting.
+        let z = if x == 0 {
+            y
+        } else {
+            let quotient: u64;
+            core::arch::asm!(
+                "div {divisor}",
+                divisor = in(reg) x - x,
+                inout("rax") y => quotient,
+                inout("rdx") 0u64 => _,
+                options(nomem, nostack),
+            );
+            quotient
+        };
+
+        user::syscall::create_word(0, z);
+        user::syscall::exit(0);
+    }
+}
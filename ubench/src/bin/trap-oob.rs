@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        // Read an unmapped address unless x is 0, in which case return y.
+        let z = if x == 0 {
+            y
+        } else {
+            core::ptr::read_volatile(core::hint::black_box(0x7fff_fff0_0000) as *const u64)
+        };
+
+        user::syscall::create_word(0, z);
+        user::syscall::exit(0);
+    }
+}
@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+extern crate user;
+
+#[inline(never)]
+#[allow(unconditional_recursion)]
+fn recurse(depth: u64) -> u64 {
+    core::hint::black_box(recurse(core::hint::black_box(depth + 1))) + 1
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    unsafe {
+        user::syscall::resize(2);
+
+        user::syscall::prompt(0);
+        user::syscall::read_tree_unchecked(0, &[0, 1]);
+
+        let mut x: u64 = 0;
+        let mut y: u64 = 0;
+        user::syscall::read_word_unchecked(0, &mut x);
+        user::syscall::read_word_unchecked(1, &mut y);
+
+        // Recurse until the stack overflows unless x is 0, in which case return y.
+        let z = if x == 0 { y } else { recurse(0) };
+
+        user::syscall::create_word(0, z);
+        user::syscall::exit(0);
+    }
+}
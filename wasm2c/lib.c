@@ -2,7 +2,24 @@
 #include <stdlib.h>
 
 #include "module.h"
+#include "wasm-rt-impl.h"
 
 size_t module_size(void) {
   return sizeof(w2c_module);
 }
+
+/* Call `add`, catching any trap it raises. Returns the trap, or
+ * WASM_RT_TRAP_NONE with the result stored in `*result`.
+ *
+ * This sets the jump buffer `wasm_rt_trap` unwinds to directly, as
+ * `wasm_rt_impl_try` also sets the exception-handling runtime's unwind target,
+ * which isn't bundled. */
+wasm_rt_trap_t module_try_add(w2c_module* module, u32 lhs, u32 rhs,
+                              u32* result) {
+  WASM_RT_SAVE_STACK_DEPTH();
+  wasm_rt_trap_t trap = WASM_RT_SETJMP(g_wasm_rt_jmp_buf);
+  if (trap == WASM_RT_TRAP_NONE) {
+    *result = w2c_module_add(module, lhs, rhs);
+  }
+  return trap;
+}
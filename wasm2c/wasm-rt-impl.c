@@ -44,6 +44,7 @@ static bool g_signal_handler_installed = false;
 static void* g_sig_handler_handle = 0;
 #else
 static char* g_alt_stack = 0;
+static WASM_RT_THREAD_LOCAL char* g_thread_alt_stack = 0;
 #endif
 #endif
 
@@ -247,6 +248,48 @@ void wasm_rt_free(void) {
 #endif
 }
 
+void wasm_rt_init_thread(void) {
+#if WASM_RT_INSTALL_SIGNAL_HANDLER && !defined(_WIN32)
+  if (g_thread_alt_stack != NULL) {
+    return;
+  }
+  g_thread_alt_stack = malloc(SIGSTKSZ);
+  if (g_thread_alt_stack == NULL) {
+    perror("malloc failed");
+    abort();
+  }
+
+  stack_t ss;
+  ss.ss_sp = g_thread_alt_stack;
+  ss.ss_flags = 0;
+  ss.ss_size = SIGSTKSZ;
+  if (sigaltstack(&ss, NULL) != 0) {
+    perror("sigaltstack failed");
+    abort();
+  }
+#endif
+}
+
+void wasm_rt_free_thread(void) {
+#if WASM_RT_INSTALL_SIGNAL_HANDLER && !defined(_WIN32)
+  if (g_thread_alt_stack == NULL) {
+    return;
+  }
+
+  stack_t ss;
+  ss.ss_sp = NULL;
+  ss.ss_flags = SS_DISABLE;
+  ss.ss_size = 0;
+  if (sigaltstack(&ss, NULL) != 0) {
+    perror("sigaltstack failed");
+    abort();
+  }
+
+  free(g_thread_alt_stack);
+  g_thread_alt_stack = NULL;
+#endif
+}
+
 #if WASM_RT_USE_MMAP
 
 static uint64_t get_allocation_size_for_mmap(wasm_rt_memory_t* memory) {
@@ -270,6 +313,86 @@ static uint64_t get_allocation_size_for_mmap(wasm_rt_memory_t* memory) {
 
 #endif
 
+#if WASM_RT_USE_MMAP && !defined(_WIN32)
+#define WASM_RT_MEMORY_POOL 1
+#define WASM_RT_MEMORY_POOL_CAPACITY 4
+
+typedef struct {
+  uint8_t* data;
+  uint64_t mmap_size;
+  /* How much of the reservation is currently readable and writable. */
+  uint64_t size;
+} pooled_memory_t;
+
+static WASM_RT_THREAD_LOCAL wasm_rt_memory_pool_t g_memory_pool_mode;
+static WASM_RT_THREAD_LOCAL pooled_memory_t
+    g_memory_pool[WASM_RT_MEMORY_POOL_CAPACITY];
+static WASM_RT_THREAD_LOCAL uint32_t g_memory_pool_count;
+
+/* Take a reservation of `mmap_size` bytes from the pool, resetting it and
+ * making exactly `byte_length` bytes accessible. */
+static uint8_t* memory_pool_take(uint64_t mmap_size, uint64_t byte_length) {
+  for (uint32_t i = 0; i < g_memory_pool_count; i++) {
+    pooled_memory_t pooled = g_memory_pool[i];
+    if (pooled.mmap_size != mmap_size) {
+      continue;
+    }
+    g_memory_pool[i] = g_memory_pool[--g_memory_pool_count];
+    if (g_memory_pool_mode == WASM_RT_MEMORY_POOL_MADVISE) {
+      if (madvise(pooled.data, pooled.size, MADV_DONTNEED) != 0) {
+        os_print_last_error("madvise failed.");
+        abort();
+      }
+    } else {
+      memset(pooled.data, 0, pooled.size);
+    }
+    int ret = 0;
+    if (byte_length > pooled.size) {
+      ret = os_mprotect(pooled.data + pooled.size, byte_length - pooled.size);
+    } else if (byte_length < pooled.size) {
+      ret = mprotect(pooled.data + byte_length, pooled.size - byte_length,
+                     PROT_NONE);
+    }
+    if (ret != 0) {
+      os_print_last_error("os_mprotect failed.");
+      abort();
+    }
+    return pooled.data;
+  }
+  return NULL;
+}
+
+/* Keep a freed reservation in the pool, if pooling is on and there's room. */
+static bool memory_pool_put(uint8_t* data,
+                            uint64_t mmap_size,
+                            uint64_t size) {
+  if (g_memory_pool_mode == WASM_RT_MEMORY_POOL_NONE ||
+      g_memory_pool_count == WASM_RT_MEMORY_POOL_CAPACITY) {
+    return false;
+  }
+  g_memory_pool[g_memory_pool_count++] =
+      (pooled_memory_t){.data = data, .mmap_size = mmap_size, .size = size};
+  return true;
+}
+#else
+#define WASM_RT_MEMORY_POOL 0
+#endif
+
+bool wasm_rt_set_memory_pool(wasm_rt_memory_pool_t mode) {
+#if WASM_RT_MEMORY_POOL
+  g_memory_pool_mode = mode;
+  if (mode == WASM_RT_MEMORY_POOL_NONE) {
+    for (uint32_t i = 0; i < g_memory_pool_count; i++) {
+      os_munmap(g_memory_pool[i].data, g_memory_pool[i].mmap_size);
+    }
+    g_memory_pool_count = 0;
+  }
+  return true;
+#else
+  return mode == WASM_RT_MEMORY_POOL_NONE;
+#endif
+}
+
 void wasm_rt_allocate_memory(wasm_rt_memory_t* memory,
                              uint64_t initial_pages,
                              uint64_t max_pages,
@@ -282,6 +405,13 @@ void wasm_rt_allocate_memory(wasm_rt_memory_t* memory,
 
 #if WASM_RT_USE_MMAP
   const uint64_t mmap_size = get_allocation_size_for_mmap(memory);
+#if WASM_RT_MEMORY_POOL
+  void* pooled = memory_pool_take(mmap_size, byte_length);
+  if (pooled) {
+    memory->data = pooled;
+    return;
+  }
+#endif
   void* addr = os_mmap(mmap_size);
   if (!addr) {
     os_print_last_error("os_mmap failed.");
@@ -348,6 +478,11 @@ uint64_t wasm_rt_grow_memory(wasm_rt_memory_t* memory, uint64_t delta) {
 void wasm_rt_free_memory(wasm_rt_memory_t* memory) {
 #if WASM_RT_USE_MMAP
   const uint64_t mmap_size = get_allocation_size_for_mmap(memory);
+#if WASM_RT_MEMORY_POOL
+  if (memory_pool_put(memory->data, mmap_size, memory->size)) {
+    return;
+  }
+#endif
   os_munmap(memory->data, mmap_size);  // ignore error
 #else
   free(memory->data);
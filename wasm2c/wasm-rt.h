@@ -160,11 +160,23 @@ extern "C" {
 #define WASM_RT_SKIP_SIGNAL_RECOVERY 0
 #endif
 
+/**
+ * The signal handler can also be requested explicitly, e.g. to detect stack
+ * overflows without stack depth counting when using BOUNDS_CHECK. (This is a
+ * local change to the upstream runtime, which always derives it.)
+ */
+#ifndef WASM_RT_INSTALL_SIGNAL_HANDLER
 #if WASM_RT_MEMCHECK_GUARD_PAGES && !WASM_RT_SKIP_SIGNAL_RECOVERY
 #define WASM_RT_INSTALL_SIGNAL_HANDLER 1
 #else
 #define WASM_RT_INSTALL_SIGNAL_HANDLER 0
 #endif
+#endif
+
+#if WASM_RT_INSTALL_SIGNAL_HANDLER && WASM_RT_SKIP_SIGNAL_RECOVERY
+#error \
+    "Cannot use both WASM_RT_INSTALL_SIGNAL_HANDLER and WASM_RT_SKIP_SIGNAL_RECOVERY"
+#endif
 
 #ifndef WASM_RT_USE_STACK_DEPTH_COUNT
 /* The signal handler on POSIX can detect call stack overflows. On windows, or
@@ -331,6 +343,18 @@ bool wasm_rt_is_initialized(void);
 /** Free the runtime's state. */
 void wasm_rt_free(void);
 
+/**
+ * Prepare the calling thread to recover from stack overflows caught by the
+ * signal handler, by giving it its own alternate signal stack. `wasm_rt_init`
+ * only does this for the thread which calls it, so any other thread calling
+ * into wasm must call this first, and `wasm_rt_free_thread` before it exits.
+ * (This is a local change to the upstream runtime.)
+ */
+void wasm_rt_init_thread(void);
+
+/** Free the calling thread's state from `wasm_rt_init_thread`. */
+void wasm_rt_free_thread(void);
+
 /**
  * A hardened jmp_buf that allows checking for initialization before use
  */
@@ -416,6 +440,30 @@ uint64_t wasm_rt_grow_memory(wasm_rt_memory_t*, uint64_t pages);
  */
 void wasm_rt_free_memory(wasm_rt_memory_t*);
 
+/**
+ * How freed Memory reservations are recycled on the calling thread. (This is a
+ * local change to the upstream runtime.)
+ *
+ * With WASM_RT_USE_MMAP on POSIX, a Memory freed while pooling is enabled is
+ * kept in a small per-thread pool rather than unmapped, and the next Memory of
+ * the same reservation size allocated on that thread reuses it. Its contents
+ * are reset either with madvise(MADV_DONTNEED), leaving the kernel to supply
+ * zero pages on the next touch, or by zeroing them eagerly with memset; either
+ * way, instantiation then copies the data segments back in.
+ */
+typedef enum {
+  WASM_RT_MEMORY_POOL_NONE,
+  WASM_RT_MEMORY_POOL_MADVISE,
+  WASM_RT_MEMORY_POOL_ZERO,
+} wasm_rt_memory_pool_t;
+
+/**
+ * Set how freed Memories are recycled on the calling thread. Setting
+ * `WASM_RT_MEMORY_POOL_NONE` unmaps every pooled reservation. Returns false if
+ * pooling isn't supported by this configuration.
+ */
+bool wasm_rt_set_memory_pool(wasm_rt_memory_pool_t mode);
+
 /**
  * Initialize a funcref Table object with an element count of `elements` and a
  * maximum size of `max_elements`.
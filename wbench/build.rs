@@ -0,0 +1,5 @@
+fn main() {
+    // Each binary is a library-like module exporting `add`, with no `main`.
+    println!("cargo::rustc-link-arg=--no-entry");
+    println!("cargo::rustc-link-arg=--export=add");
+}
@@ -0,0 +1,7 @@
+#![no_std]
+#![no_main]
+
+#[unsafe(no_mangle)]
+pub extern "C" fn add(lhs: u32, _rhs: u32) -> u32 {
+    wbench::run(kernels::sha256::run, lhs)
+}
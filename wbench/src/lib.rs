@@ -0,0 +1,17 @@
+//! Wasm modules wrapping the [`kernels`], one binary per workload. Each exports
+//! `add(seed, _) -> digest`, matching the calling convention of the hand-written workloads.
+
+#![no_std]
+
+#[panic_handler]
+fn panic(_: &core::panic::PanicInfo) -> ! {
+    core::arch::wasm32::unreachable()
+}
+
+static mut SCRATCH: [u8; kernels::SCRATCH_SIZE] = [0; kernels::SCRATCH_SIZE];
+
+/// Run `workload` on the module's scratch memory.
+pub fn run(workload: fn(u32, &mut [u8]) -> u32, seed: u32) -> u32 {
+    // Calls into a module never overlap.
+    workload(seed, unsafe { &mut *&raw mut SCRATCH })
+}